@@ -56,7 +56,7 @@ impl Parse for IterOverFields {
 		input.parse::<Token![in]>()?;
 		let iter = input.parse()?;
 
-		let binds = if let Some(_) = input.parse::<Option<Token![where]>>()? {
+		let binds = if input.parse::<Option<Token![where]>>()?.is_some() {
 			Punctuated::parse_separated_nonempty(input)?
 		} else {
 			Punctuated::new()
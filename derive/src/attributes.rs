@@ -0,0 +1,67 @@
+use super::crate_name;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{Attribute, Error, Lit, Meta, NestedMeta, Result};
+
+/// What a collection field does with elements that fail to deserialize, from
+/// `#[qj(element_failure = "drop" | "fail" | "default")]`.
+#[derive(Clone, Copy)]
+pub enum ElementFailure {
+	Drop,
+	Fail,
+	Default
+}
+
+impl ElementFailure {
+	pub fn tokens(self) -> TokenStream {
+		let crate_ = crate_name();
+		let path = quote! {::#crate_::deserialize::r#trait::ElementFailure};
+
+		match self {
+			Self::Drop => quote! {#path::Drop},
+			Self::Fail => quote! {#path::Fail},
+			Self::Default => quote! {#path::Substitute(::core::default::Default::default)}
+		}
+	}
+}
+
+#[derive(Default)]
+pub struct FieldAttributes {
+	pub element_failure: Option<ElementFailure>
+}
+
+impl FieldAttributes {
+	pub fn parse(attributes: &[Attribute]) -> Result<Self> {
+		let mut result = Self::default();
+
+		for attribute in attributes.iter().filter(|attribute| attribute.path.is_ident("qj")) {
+			let list = match attribute.parse_meta()? {
+				Meta::List(list) => list,
+				meta => return Err(Error::new_spanned(meta, "expected #[qj(...)]"))
+			};
+
+			for nested in list.nested {
+				match nested {
+					NestedMeta::Meta(Meta::NameValue(name_value))
+							if name_value.path.is_ident("element_failure") => {
+						let value = match &name_value.lit {
+							Lit::Str(value) => value,
+							lit => return Err(Error::new_spanned(lit, "expected a string"))
+						};
+
+						result.element_failure = Some(match value.value().as_str() {
+							"drop" => ElementFailure::Drop,
+							"fail" => ElementFailure::Fail,
+							"default" => ElementFailure::Default,
+							_ => return Err(Error::new_spanned(value,
+								"expected \"drop\", \"fail\" or \"default\""))
+						});
+					},
+					nested => return Err(Error::new_spanned(nested, "unknown qj attribute"))
+				}
+			}
+		}
+
+		Ok(result)
+	}
+}
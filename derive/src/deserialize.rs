@@ -1,33 +1,101 @@
-use super::crate_name;
+use super::{attributes::FieldAttributes, crate_name};
 use proc_macro2::{TokenStream, Ident, Span};
 use quote::quote;
 use syn::{
 	punctuated::Punctuated, Token,
 	Data, DataEnum, DataStruct, DeriveInput, Field, Fields, FieldsNamed,
-	FieldsUnnamed, Variant, LitStr
+	FieldsUnnamed, Generics, Type, Variant, LitStr
 };
 use qj_derive_helpers::iter_over_fields;
 
-fn impl_struct(name: &Ident, fields: Punctuated<Field, Token![,]>)
-		-> TokenStream {
+fn parse_attributes(fields: &Punctuated<Field, Token![,]>)
+		-> syn::Result<Vec<FieldAttributes>> {
+	fields.iter()
+		.map(|field| FieldAttributes::parse(&field.attrs))
+		.collect()
+}
+
+/// Expression deserializing `value` as `ty` into an `Option<ty>`.
+fn deserialize_field(ty: &Type, attributes: &FieldAttributes) -> TokenStream {
+	let crate_ = crate_name();
+	match attributes.element_failure {
+		Some(element_failure) => {
+			let element_failure = element_failure.tokens();
+			quote! {<#ty as ::#crate_::deserialize::r#trait::DeserializeElements<'s, E>>::deserialize_elements(value, error_context, #element_failure)?}
+		},
+		None => quote! {<#ty as ::#crate_::deserialize::r#trait::Deserialize<'s, E>>::deserialize(value, error_context)?}
+	}
+}
+
+/// Deserializes named fields from an object, constructing `path {...}`.
+fn impl_struct(path: &TokenStream, fields: &Punctuated<Field, Token![,]>)
+		-> (TokenStream, Option<TokenStream>) {
 	let crate_ = crate_name();
+	let attributes = match parse_attributes(fields) {
+		Ok(attributes) => attributes,
+		Err(error) => return (error.to_compile_error(), None)
+	};
 
 	iter_over_fields! {
 		for (index, field) in fields.iter().enumerate() where
 				variable = Ident::new(&format!("field_{}", index), Span::call_site()),
 				ty = &field.ty,
+				element_failure = attributes[index].element_failure
+					.map(|element_failure| element_failure.tokens()),
 				field = field.ident.as_ref().unwrap(),
 				field_str = LitStr::new(&field.to_string(), field.span()) {
 			let field_definitions = quote! {let mut #variable: Option<Option<#ty>> = None;};
-			let field_name_match = quote! {#field_str => #variable = Some(::#crate_::deserialize::r#trait::Deserialize::deserialize(value, error_context)?)};
+			let field_name_match = match &element_failure {
+				Some(element_failure) => quote! {#field_str => #variable = Some(<#ty as ::#crate_::deserialize::r#trait::DeserializeElements<'s, E>>::deserialize_elements(value, error_context, #element_failure)?)},
+				None => quote! {#field_str => #variable = Some(::#crate_::deserialize::r#trait::Deserialize::deserialize(value, error_context)?)}
+			};
 			let field_presence_tuple = quote! {#variable};
 			let field_presence_partial_match = quote! {Some(_)};
 			let field_presence_full_match = quote! {Some(Some(#variable))};
 			let field_presence_action = quote! {#field: #variable};
+			let field_in_place_definitions = quote! {let mut #variable: Option<bool> = None;};
+			let field_in_place_match = match &element_failure {
+				Some(element_failure) => quote! {#field_str => #variable = Some(<#ty as ::#crate_::deserialize::r#trait::DeserializeElements<'s, E>>::deserialize_elements_in_place(&mut self.#field, value, error_context, #element_failure)?)},
+				None => quote! {#field_str => #variable = Some(::#crate_::deserialize::r#trait::Deserialize::deserialize_in_place(&mut self.#field, value, error_context)?)}
+			};
+			let field_in_place_tuple = quote! {#variable};
+			let field_in_place_partial_match = quote! {Some(_)};
+			let field_in_place_full_match = quote! {Some(true)};
 		}
 	}
 
-	quote! {
+	let deserialize_in_place = quote! {
+		match value {
+			ValueDeserializer::Object(mut object) => {
+				#(#field_in_place_definitions)*
+
+				while let Some(entry) = object.next_entry()? {
+					let (name, value) = entry.accept()?;
+					::#crate_::deserialize::error::ErrorContext::push_key(error_context, KeyKind::Object(name.clone()));
+					match name.as_ref() {
+						#(#field_in_place_match,)*
+						_ => ()
+					}
+					::#crate_::deserialize::error::ErrorContext::pop_key(error_context);
+				}
+
+				match (#(#field_in_place_tuple),*) {
+					(#(#field_in_place_full_match),*) => Ok(true),
+					(#(#field_in_place_partial_match),*) => Ok(false),
+					_ => {
+						::#crate_::deserialize::error::ErrorContext::report_missing_fields(error_context);
+						Ok(false)
+					}
+				}
+			},
+			unexpected => {
+				::#crate_::deserialize::error::ErrorContext::report_unexpected_type(error_context, unexpected.kind(), &[JSONType::Object]);
+				Ok(false)
+			}
+		}
+	};
+
+	let deserialize = quote! {
 		match value {
 			ValueDeserializer::Object(mut object) => {
 				#(#field_definitions)*
@@ -43,7 +111,7 @@ fn impl_struct(name: &Ident, fields: Punctuated<Field, Token![,]>)
 				}
 
 				match (#(#field_presence_tuple),*) {
-					(#(#field_presence_full_match),*) => Ok(Some(#name {
+					(#(#field_presence_full_match),*) => Ok(Some(#path {
 						#(#field_presence_action),*
 					})),
 					(#(#field_presence_partial_match),*) => Ok(None),
@@ -58,36 +126,288 @@ fn impl_struct(name: &Ident, fields: Punctuated<Field, Token![,]>)
 				Ok(None)
 			}
 		}
+	};
+
+	(deserialize, Some(deserialize_in_place))
+}
+
+/// Deserializes unnamed fields, constructing `path(...)`. A single field is
+/// deserialized as is, any other number of them from an array.
+fn impl_tuple(path: &TokenStream, fields: &Punctuated<Field, Token![,]>) -> TokenStream {
+	let crate_ = crate_name();
+	let attributes = match parse_attributes(fields) {
+		Ok(attributes) => attributes,
+		Err(error) => return error.to_compile_error()
+	};
+
+	if fields.len() == 1 {
+		let field = deserialize_field(&fields[0].ty, &attributes[0]);
+		return quote! {Ok(#field.map(#path))}
+	}
+
+	let length = fields.len();
+	let variables: Vec<_> = (0..length)
+		.map(|index| Ident::new(&format!("field_{}", index), Span::call_site()))
+		.collect();
+	let reads = fields.iter().zip(&attributes).zip(&variables).enumerate()
+		.map(|(index, ((field, attributes), variable))| {
+			let field = deserialize_field(&field.ty, attributes);
+			quote! {
+				let #variable = match array.next_entry()? {
+					Some(value) => {
+						::#crate_::deserialize::error::ErrorContext::push_key(error_context, KeyKind::Array(#index));
+						let value = #field;
+						::#crate_::deserialize::error::ErrorContext::pop_key(error_context);
+						value
+					},
+					None => {
+						::#crate_::deserialize::error::ErrorContext::report_array_too_short(error_context, #length, #index);
+						break 'tuple Ok(None)
+					}
+				};
+			}
+		});
+
+	quote! {
+		match value {
+			ValueDeserializer::Array(mut array) => 'tuple: {
+				#(#reads)*
+
+				let mut length = #length;
+				while array.next_entry()?.is_some() {
+					length += 1;
+				}
+				if length != #length {
+					::#crate_::deserialize::error::ErrorContext::report_array_too_long(error_context, #length, length);
+					break 'tuple Ok(None)
+				}
+
+				match (#(#variables,)*) {
+					(#(Some(#variables),)*) => Ok(Some(#path(#(#variables),*))),
+					_ => Ok(None)
+				}
+			},
+			unexpected => {
+				::#crate_::deserialize::error::ErrorContext::report_unexpected_type(error_context, unexpected.kind(), &[JSONType::Array]);
+				Ok(None)
+			}
+		}
 	}
 }
 
-fn impl_tuple_struct(_fields: Punctuated<Field, Token![,]>) -> TokenStream {
-	todo!()
+/// Deserializes a unit struct or variant from `null`.
+fn impl_unit(path: &TokenStream) -> TokenStream {
+	let crate_ = crate_name();
+	quote! {
+		Ok(<() as ::#crate_::deserialize::r#trait::Deserialize<'s, E>>::deserialize(value, error_context)?
+			.map(|()| #path))
+	}
 }
 
-fn impl_enum(_variants: Punctuated<Variant, Token![,]>) -> TokenStream {
-	todo!()
+fn unit_variants(variants: &Punctuated<Variant, Token![,]>)
+		-> Option<Vec<(&Ident, LitStr)>> {
+	variants.iter()
+		.map(|variant| match variant.fields {
+			Fields::Unit => Some((&variant.ident,
+				LitStr::new(&variant.ident.to_string(), variant.ident.span()))),
+			_ => None
+		})
+		.collect()
 }
 
-pub fn derive(item: DeriveInput) -> TokenStream {
+fn impl_unit_enum_key(name: &Ident, variants: &[(&Ident, LitStr)],
+		key: TokenStream) -> TokenStream {
+	let crate_ = crate_name();
+	let variant_idents = variants.iter().map(|(variant, _)| variant);
+	let variant_strs = variants.iter().map(|(_, variant_str)| variant_str);
+	let expected = variant_strs.clone();
+
+	quote! {
+		match #key {
+			#(#variant_strs => Some(#name::#variant_idents),)*
+			_ => {
+				::#crate_::deserialize::error::ErrorContext::report_unknown_variant(error_context, &[#(#expected),*]);
+				None
+			}
+		}
+	}
+}
+
+/// Deserializes an externally tagged enum: unit variants from their name as
+/// a string, and every variant from an object with a single entry mapping its
+/// name to its fields.
+fn impl_enum(name: &Ident, variants: Punctuated<Variant, Token![,]>)
+		-> TokenStream {
+	let crate_ = crate_name();
+	let variant_strs: Vec<_> = variants.iter()
+		.map(|variant| LitStr::new(&variant.ident.to_string(), variant.ident.span()))
+		.collect();
+	let units: Vec<_> = variants.iter().zip(&variant_strs)
+		.filter(|(variant, _)| matches!(variant.fields, Fields::Unit))
+		.map(|(variant, variant_str)| (&variant.ident, variant_str.clone()))
+		.collect();
+	let data = units.len() != variants.len();
+
+	let mut expected = Vec::new();
+	let from_string = (!units.is_empty()).then(|| {
+		expected.push(quote! {JSONType::String});
+		let from_string = impl_unit_enum_key(name, &units, quote! {string.as_ref()});
+		quote! {
+			ValueDeserializer::String(string) => {
+				let string = string.accept()?;
+				Ok(#from_string)
+			},
+		}
+	});
+
+	let from_object = data.then(|| {
+		expected.push(quote! {JSONType::Object});
+		let bodies = variants.iter().map(|variant| {
+			let variant_name = &variant.ident;
+			let path = quote! {#name::#variant_name};
+			match &variant.fields {
+				Fields::Named(FieldsNamed {named, ..}) => impl_struct(&path, named).0,
+				Fields::Unnamed(FieldsUnnamed {unnamed, ..}) => impl_tuple(&path, unnamed),
+				Fields::Unit => impl_unit(&path)
+			}
+		});
+		let variant_strs = variant_strs.iter();
+		let expected = variant_strs.clone();
+
+		quote! {
+			ValueDeserializer::Object(mut object) => 'variant: {
+				let Some(entry) = object.next_entry()? else {
+					::#crate_::deserialize::error::ErrorContext::report_variant_entries(error_context, 0);
+					break 'variant Ok(None)
+				};
+
+				let result = {
+					let (name, value) = entry.accept()?;
+					::#crate_::deserialize::error::ErrorContext::push_key(error_context, KeyKind::Object(name.clone()));
+					let result = match name.as_ref() {
+						#(#variant_strs => #bodies,)*
+						_ => {
+							::#crate_::deserialize::error::ErrorContext::report_unknown_variant(error_context, &[#(#expected),*]);
+							Ok(None)
+						}
+					};
+					::#crate_::deserialize::error::ErrorContext::pop_key(error_context);
+					result
+				};
+
+				let mut entries = 1;
+				while object.next_entry()?.is_some() {
+					entries += 1;
+				}
+				if entries != 1 {
+					::#crate_::deserialize::error::ErrorContext::report_variant_entries(error_context, entries);
+					break 'variant Ok(None)
+				}
+				result
+			},
+		}
+	});
+
+	quote! {
+		match value {
+			#from_string
+			#from_object
+			unexpected => {
+				::#crate_::deserialize::error::ErrorContext::report_unexpected_type(error_context, unexpected.kind(), &[#(#expected),*]);
+				Ok(None)
+			}
+		}
+	}
+}
+
+pub fn derive_key(item: DeriveInput) -> TokenStream {
 	let DeriveInput {ident: name, data, ..} = item;
 	let crate_ = crate_name();
 
-	let deserialize = match data {
+	let deserialize_key = match data {
+		Data::Enum(DataEnum {variants, ..}) => match unit_variants(&variants) {
+			Some(variants) =>
+				impl_unit_enum_key(&name, &variants, quote! {key.as_ref()}),
+			None => return syn::Error::new(name.span(),
+				"DeserializeKey can only be derived for enums without fields")
+					.to_compile_error()
+		},
+		_ => return syn::Error::new(name.span(),
+			"DeserializeKey can only be derived for enums without fields")
+				.to_compile_error()
+	};
+
+	quote! {
+		type Option<T> =
+			::core::option::Option<T>;
+
+		#[automatically_derived]
+		impl<'s, E> ::#crate_::deserialize::r#trait::DeserializeKey<'s, E> for #name
+				where E: ::#crate_::deserialize::error::ErrorContext<'s> {
+			fn deserialize_key(key: ::std::borrow::Cow<'s, str>,
+					error_context: &mut E) -> Option<Self> {
+				#deserialize_key
+			}
+		}
+	}
+}
+
+/// Ties the lifetime parameter a type may have to the lifetime of the input,
+/// giving the extra parameters, arguments and bounds of the implementation.
+fn input_lifetime(generics: &Generics)
+		-> syn::Result<(TokenStream, TokenStream, TokenStream)> {
+	let mut lifetimes = generics.lifetimes();
+	let lifetime = match (generics.type_params().next(), generics.const_params().next(),
+			lifetimes.next(), lifetimes.next()) {
+		(None, None, lifetime, None) => lifetime.map(|param| &param.lifetime),
+		_ => return Err(syn::Error::new_spanned(generics,
+			"Deserialize can only be derived for types with at most one lifetime parameter"))
+	};
+
+	Ok(match lifetime {
+		None => (quote! {}, quote! {}, quote! {}),
+		Some(lifetime) if lifetime.ident == "s" => (quote! {}, quote! {<'s>}, quote! {}),
+		Some(lifetime) =>
+			(quote! {#lifetime,}, quote! {<#lifetime>}, quote! {#lifetime: 's, 's: #lifetime,})
+	})
+}
+
+pub fn derive(item: DeriveInput) -> TokenStream {
+	let DeriveInput {ident: name, generics, data, ..} = item;
+	let crate_ = crate_name();
+
+	let (parameters, arguments, bounds) = match input_lifetime(&generics) {
+		Ok(lifetime) => lifetime,
+		Err(error) => return error.to_compile_error()
+	};
+
+	let path = quote! {#name};
+	let (deserialize, deserialize_in_place) = match data {
 		Data::Struct(DataStruct {fields, ..}) => match fields {
 			Fields::Named(FieldsNamed {named, ..}) =>
-				impl_struct(&name, named),
+				impl_struct(&path, &named),
 			Fields::Unnamed(FieldsUnnamed {unnamed, ..}) =>
-				impl_tuple_struct(unnamed),
+				(impl_tuple(&path, &unnamed), None),
 			Fields::Unit =>
-				todo!()
+				(impl_unit(&path), None)
 		},
+		Data::Enum(DataEnum {variants, ..}) if variants.is_empty() =>
+			return syn::Error::new(name.span(), "Deserialize cannot be derived for enums without variants")
+				.to_compile_error(),
 		Data::Enum(DataEnum {variants, ..}) =>
-			impl_enum(variants),
+			(impl_enum(&name, variants), None),
 		Data::Union(_) =>
-			todo!()
+			return syn::Error::new(name.span(), "Deserialize cannot be derived for unions")
+				.to_compile_error()
 	};
 
+	let deserialize_in_place = deserialize_in_place.map(|deserialize_in_place| quote! {
+		fn deserialize_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+				error_context: &mut E) -> Result<bool, SyntaxError> {
+			#deserialize_in_place
+		}
+	});
+
 	quote! {
 		type Result<T, E> =
 			::core::result::Result<T, E>;
@@ -105,12 +425,14 @@ pub fn derive(item: DeriveInput) -> TokenStream {
 			::#crate_::deserialize::error::KeyKind<'s>;
 
 		#[automatically_derived]
-		impl<'s, E> ::#crate_::deserialize::r#trait::Deserialize<'s, E> for #name
-				where E: ::#crate_::deserialize::error::ErrorContext<'s> {
+		impl<'s, #parameters E> ::#crate_::deserialize::r#trait::Deserialize<'s, E> for #name #arguments
+				where #bounds E: ::#crate_::deserialize::error::ErrorContext<'s> {
 			fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
 					error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
 				#deserialize
 			}
+
+			#deserialize_in_place
 		}
 	}
 }
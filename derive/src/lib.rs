@@ -1,6 +1,7 @@
+mod attributes;
 mod deserialize;
+mod serialize;
 
-use self::deserialize::derive;
 use proc_macro::TokenStream;
 use proc_macro2::{Ident, Span};
 use quote::quote;
@@ -10,15 +11,46 @@ fn crate_name() -> Ident {
 	Ident::new("qj", Span::call_site())
 }
 
-#[proc_macro_derive(Deserialize)]
+#[proc_macro_derive(Deserialize, attributes(qj))]
 pub fn derive_deserialize(item: TokenStream) -> TokenStream {
-	let output = derive(parse_macro_input!(item as DeriveInput));
+	let output = deserialize::derive(parse_macro_input!(item as DeriveInput));
 
 	TokenStream::from(quote! {
 		const _: () = {
 			#output
+		};
+	})
+}
+
+#[proc_macro_derive(DeserializeKey)]
+pub fn derive_deserialize_key(item: TokenStream) -> TokenStream {
+	let output = deserialize::derive_key(parse_macro_input!(item as DeriveInput));
+
+	TokenStream::from(quote! {
+		const _: () = {
+			#output
+		};
+	})
+}
+
+#[proc_macro_derive(Serialize, attributes(qj))]
+pub fn derive_serialize(item: TokenStream) -> TokenStream {
+	let output = serialize::derive(parse_macro_input!(item as DeriveInput));
 
-			()
+	TokenStream::from(quote! {
+		const _: () = {
+			#output
+		};
+	})
+}
+
+#[proc_macro_derive(SerializeKey)]
+pub fn derive_serialize_key(item: TokenStream) -> TokenStream {
+	let output = serialize::derive_key(parse_macro_input!(item as DeriveInput));
+
+	TokenStream::from(quote! {
+		const _: () = {
+			#output
 		};
 	})
 }
@@ -0,0 +1,200 @@
+use super::{attributes::FieldAttributes, crate_name};
+use proc_macro2::{TokenStream, Ident, Span};
+use quote::quote;
+use syn::{
+	parse_quote, punctuated::Punctuated, Token,
+	Data, DataEnum, DataStruct, DeriveInput, Field, Fields, FieldsNamed,
+	FieldsUnnamed, Index, LitStr, Variant
+};
+
+/// Field attributes only affect deserialization, but are still validated so
+/// that both derives accept the same ones.
+fn check_attributes(fields: &Punctuated<Field, Token![,]>) -> Option<TokenStream> {
+	fields.iter()
+		.map(|field| FieldAttributes::parse(&field.attrs))
+		.find_map(Result::err)
+		.map(|error| error.to_compile_error())
+}
+
+/// Serializes named fields, bound to the given variables, as an object.
+fn impl_struct(fields: &Punctuated<Field, Token![,]>, variables: &[TokenStream])
+		-> TokenStream {
+	if let Some(error) = check_attributes(fields) {
+		return error
+	}
+
+	let field_strs = fields.iter().map(|field| {
+		let field = field.ident.as_ref().unwrap();
+		LitStr::new(&field.to_string(), field.span())
+	});
+
+	quote! {
+		let mut object = value.object()?;
+		#(object.field(#field_strs, #variables)?;)*
+		object.end()
+	}
+}
+
+/// Serializes unnamed fields, bound to the given variables. A single field is
+/// serialized as is, any other number of them as an array.
+fn impl_tuple(fields: &Punctuated<Field, Token![,]>, variables: &[TokenStream])
+		-> TokenStream {
+	let crate_ = crate_name();
+	if let Some(error) = check_attributes(fields) {
+		return error
+	}
+
+	match variables {
+		[variable] => quote! {
+			::#crate_::serialize::r#trait::Serialize::serialize(#variable, value)
+		},
+		variables => quote! {
+			let mut array = value.array()?;
+			#(array.push(#variables)?;)*
+			array.end()
+		}
+	}
+}
+
+fn field_variables(fields: &Fields) -> Vec<Ident> {
+	(0..fields.len())
+		.map(|index| Ident::new(&format!("field_{}", index), Span::call_site()))
+		.collect()
+}
+
+/// Serializes an externally tagged enum: unit variants as their name, and
+/// other variants as an object with a single entry mapping their name to
+/// their fields.
+fn impl_enum(name: &Ident, variants: Punctuated<Variant, Token![,]>)
+		-> TokenStream {
+	let arms = variants.iter().map(|variant| {
+		let variant_name = &variant.ident;
+		let variant_str = LitStr::new(&variant_name.to_string(), variant_name.span());
+		let variables = field_variables(&variant.fields);
+		let bound: Vec<_> = variables.iter().map(|variable| quote! {#variable}).collect();
+
+		let (pattern, fields) = match &variant.fields {
+			Fields::Named(FieldsNamed {named, ..}) => {
+				let field_names = named.iter().map(|field| field.ident.as_ref().unwrap());
+				(quote! {{#(#field_names: #variables),*}}, impl_struct(named, &bound))
+			},
+			Fields::Unnamed(FieldsUnnamed {unnamed, ..}) =>
+				(quote! {(#(#variables),*)}, impl_tuple(unnamed, &bound)),
+			Fields::Unit =>
+				return quote! {#name::#variant_name => value.string(#variant_str)}
+		};
+
+		quote! {
+			#name::#variant_name #pattern => {
+				let mut object = value.object()?;
+				{
+					let value = object.entry(#variant_str)?;
+					#fields?;
+				}
+				object.end()
+			}
+		}
+	});
+
+	quote! {
+		match self {
+			#(#arms,)*
+		}
+	}
+}
+
+pub fn derive_key(item: DeriveInput) -> TokenStream {
+	let DeriveInput {ident: name, data, ..} = item;
+	let crate_ = crate_name();
+
+	let variants = match data {
+		Data::Enum(DataEnum {variants, ..})
+				if variants.iter().all(|variant| matches!(variant.fields, Fields::Unit)) =>
+			variants,
+		_ => return syn::Error::new(name.span(),
+			"SerializeKey can only be derived for enums without fields")
+				.to_compile_error()
+	};
+
+	let variant_idents = variants.iter().map(|variant| &variant.ident);
+	let variant_strs = variants.iter()
+		.map(|variant| LitStr::new(&variant.ident.to_string(), variant.ident.span()));
+
+	quote! {
+		#[automatically_derived]
+		impl ::#crate_::serialize::r#trait::SerializeKey for #name {
+			fn serialize_key(&self) -> ::std::borrow::Cow<'_, str> {
+				::std::borrow::Cow::Borrowed(match self {
+					#(#name::#variant_idents => #variant_strs,)*
+				})
+			}
+		}
+	}
+}
+
+pub fn derive(item: DeriveInput) -> TokenStream {
+	let DeriveInput {ident: name, mut generics, data, ..} = item;
+	let crate_ = crate_name();
+
+	// Fields of a type parameter's type can only be serialized if it can.
+	let type_params: Vec<_> = generics.type_params().map(|param| param.ident.clone()).collect();
+	let where_clause = generics.make_where_clause();
+	for param in type_params {
+		where_clause.predicates.push(parse_quote! {
+			#param: ::#crate_::serialize::r#trait::Serialize
+		});
+	}
+	let (impl_generics, type_generics, where_clause) = generics.split_for_impl();
+
+	let serialize = match data {
+		Data::Struct(DataStruct {fields, ..}) => {
+			let variables = match &fields {
+				Fields::Named(FieldsNamed {named, ..}) => named.iter()
+					.map(|field| {
+						let field = field.ident.as_ref().unwrap();
+						quote! {&self.#field}
+					})
+					.collect(),
+				fields => (0..fields.len())
+					.map(|index| {
+						let index = Index::from(index);
+						quote! {&self.#index}
+					})
+					.collect::<Vec<_>>()
+			};
+
+			match &fields {
+				Fields::Named(FieldsNamed {named, ..}) => impl_struct(named, &variables),
+				Fields::Unnamed(FieldsUnnamed {unnamed, ..}) => impl_tuple(unnamed, &variables),
+				Fields::Unit => quote! {value.null()}
+			}
+		},
+		Data::Enum(DataEnum {variants, ..}) if variants.is_empty() =>
+			return syn::Error::new(name.span(), "Serialize cannot be derived for enums without variants")
+				.to_compile_error(),
+		Data::Enum(DataEnum {variants, ..}) =>
+			impl_enum(&name, variants),
+		Data::Union(_) =>
+			return syn::Error::new(name.span(), "Serialize cannot be derived for unions")
+				.to_compile_error()
+	};
+
+	quote! {
+		type Result<T, E> =
+			::core::result::Result<T, E>;
+
+		type ValueSerializer<'a, 'w, F> =
+			::#crate_::serialize::ValueSerializer<'a, 'w, F>;
+		type Error =
+			::#crate_::serialize::Error;
+
+		#[automatically_derived]
+		impl #impl_generics ::#crate_::serialize::r#trait::Serialize for #name #type_generics
+				#where_clause {
+			fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+					where F: ::#crate_::serialize::format::Formatter {
+				#serialize
+			}
+		}
+	}
+}
@@ -1,4 +1,5 @@
 use super::{/*Location,*/ ValueDeserializer};
+use crate::timestamp::TimestampError;
 use const_format::formatcp;
 use std::{
 	borrow::Cow,
@@ -18,6 +19,7 @@ pub enum SyntaxError {
 	StringUnterminated,
 	StringUnexpectedControlChar,
 	StringUnexpectedEscape(char),
+	StringInvalidUnicodeEscape,
 
 	NumberIncomplete,
 	NumberExpectedDigit
@@ -37,8 +39,8 @@ impl SyntaxError {
 			Some(char) => expected.contains(&char),
 			None => end_expected
 		}
-			.then(|| ())
-			.ok_or_else(|| Self::Unexpected {
+			.then_some(())
+			.ok_or(Self::Unexpected {
 				unexpected: char,
 				expected,
 				end_expected,
@@ -47,6 +49,43 @@ impl SyntaxError {
 	}
 }
 
+impl Display for SyntaxError {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		match self {
+			Self::Unexpected {unexpected, expected, end_expected, location} => {
+				match unexpected {
+					Some(char) => write!(f, "unexpected {:?} at {}, expected ", char, location)?,
+					None => write!(f, "unexpected end of input at {}, expected ", location)?
+				}
+
+				let mut expected = expected.iter().map(|char| format!("{:?}", char))
+					.chain(end_expected.then(|| "end of input".to_owned()))
+					.peekable();
+				while let Some(item) = expected.next() {
+					f.write_str(&item)?;
+					if expected.peek().is_some() {
+						f.write_str(" or ")?;
+					}
+				}
+				Ok(())
+			},
+
+			Self::StringUnterminated => f.write_str("unterminated string"),
+			Self::StringUnexpectedControlChar =>
+				f.write_str("unexpected control character in string"),
+			Self::StringUnexpectedEscape(char) =>
+				write!(f, "unexpected escape sequence \\{}", char),
+			Self::StringInvalidUnicodeEscape =>
+				f.write_str("invalid unicode escape sequence in string"),
+
+			Self::NumberIncomplete => f.write_str("incomplete number"),
+			Self::NumberExpectedDigit => f.write_str("expected a digit")
+		}
+	}
+}
+
+impl std::error::Error for SyntaxError {}
+
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub enum KeyKind<'s> {
 	Object(Cow<'s, str>),
@@ -64,6 +103,15 @@ impl<'s> KeyKind<'s> {
 	}
 }
 
+impl Display for KeyKind<'_> {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		match self {
+			Self::Object(name) => write!(f, ".{}", name),
+			Self::Array(index) => write!(f, "[{}]", index)
+		}
+	}
+}
+
 macro_rules! impl_numeric {
 	($name:ident, $unsigned:literal, $signed:literal, $($constant:ident),*) => {
 		pub fn $name(self) -> &'static str {
@@ -137,6 +185,28 @@ impl NumericPrimitive {
 	impl_numeric!(mention_by_noun, "an unsigned {} bit integer", "a signed {} bit integer", BITS);
 }
 
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum FloatPrimitive {
+	F32,
+	F64
+}
+
+impl FloatPrimitive {
+	pub fn noun(self) -> &'static str {
+		match self {
+			Self::F32 => "32 bit float",
+			Self::F64 => "64 bit float"
+		}
+	}
+
+	pub fn mention_by_noun(self) -> &'static str {
+		match self {
+			Self::F32 => "a 32 bit float",
+			Self::F64 => "a 64 bit float"
+		}
+	}
+}
+
 #[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub enum JSONType {
 	/// Any object.
@@ -197,9 +267,64 @@ impl Display for JSONType {
 	}
 }
 
+/// How built-in implementations treat values whose JSON type doesn't match
+/// the target type.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Coercion {
+	/// Only values of the expected JSON type are accepted.
+	#[default]
+	Strict,
+	/// Compatible representations are converted, such as `"42"` for an
+	/// integer, `"true"` or `1` for a boolean and `42` for a string. Each
+	/// conversion is reported through [`ErrorContext::report_coerced`].
+	Lenient
+}
+
+/// What built-in float implementations do with numbers outside the range of
+/// the target type.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum FloatRange {
+	/// Numbers too large in magnitude are reported through
+	/// [`ErrorContext::report_float_overflow`], and nonzero numbers too small
+	/// to be represented through [`ErrorContext::report_float_underflow`].
+	#[default]
+	Error,
+	/// Numbers too large in magnitude become the largest finite value of the
+	/// same sign, and numbers too small become zero.
+	Saturate,
+	/// Numbers too large in magnitude become infinities, and numbers too small
+	/// become zero.
+	Accept
+}
+
+/// Whether built-in set and map implementations report elements or keys that
+/// are already present.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Duplicates {
+	/// Duplicate elements are silently merged, and the last value of a
+	/// duplicate key is kept.
+	#[default]
+	Allow,
+	/// Duplicate elements are reported through
+	/// [`ErrorContext::report_duplicate_element`] and duplicate keys through
+	/// [`ErrorContext::report_duplicate_key`], and then treated as above.
+	Report
+}
+
 pub trait ErrorContext<'s>: Sized {
 	fn report_unknown<M>(&mut self, message: M)
 		where M: ToString;
+	fn report_warning<M>(&mut self, _message: M)
+		where M: ToString {}
+
+	fn coercion(&self) -> Coercion {
+		Coercion::Strict
+	}
+	fn report_coerced(&mut self, found: JSONType, expected: JSONType) {
+		self.report_warning(format!("coerced {} into {}",
+			found.mention_by_noun(), expected.mention_by_noun()))
+	}
+
 	fn report_unexpected_type(&mut self, unexpected: JSONType,
 			expected: &[JSONType]) {
 		match expected.len() {
@@ -216,18 +341,20 @@ pub trait ErrorContext<'s>: Sized {
 				unexpected.mention_by_noun()
 			)),
 			len => {
-				expected.iter().enumerate()
+				let message = expected.iter().enumerate()
 					.fold(String::from("expected "), |mut string, (index, token)| {
 						match index {
 							0 =>
-								string.push_str(&format!("{}", token.mention_by_noun())),
+								string.push_str(token.mention_by_noun()),
 							index if len - 1 == index =>
-								string.push_str(&format!("or {}", token)),
+								string.push_str(&format!(", or {}", token)),
 							_ =>
 								string.push_str(&format!(", {}", token))
 						}
 						string
 					});
+				self.report_unknown(format!("{}, found {}",
+					message, unexpected.mention_by_noun()))
 			}
 		}
 	}
@@ -236,6 +363,14 @@ pub trait ErrorContext<'s>: Sized {
 		self.report_unknown(
 			"expected a string borrowed from source, found an owned string")
 	}
+	fn report_string_unparsable<M>(&mut self, error: M)
+			where M: Display {
+		self.report_unknown(format!("string cannot be parsed: {}", error))
+	}
+	fn report_string_expected_char(&mut self) {
+		self.report_unknown(
+			"expected a string containing a single character")
+	}
 
 	fn report_number_overflow(&mut self, r#type: NumericPrimitive) {
 		self.report_unknown(format!("value causes an integer overflow in target ({})", r#type.mention_by_noun()))
@@ -243,15 +378,68 @@ pub trait ErrorContext<'s>: Sized {
 	fn report_number_underflow(&mut self, r#type: NumericPrimitive) {
 		self.report_unknown(format!("value causes an integer overflow in target ({})", r#type.mention_by_noun()))
 	}
+	fn report_number_zero(&mut self, r#type: NumericPrimitive) {
+		self.report_unknown(format!("value cannot be zero in target (a non-zero {})", r#type.noun()))
+	}
+	fn float_range(&self) -> FloatRange {
+		FloatRange::Error
+	}
+	fn report_float_overflow(&mut self, r#type: FloatPrimitive) {
+		self.report_unknown(format!("value is too large to be represented by target ({})", r#type.mention_by_noun()))
+	}
+	fn report_float_underflow(&mut self, r#type: FloatPrimitive) {
+		self.report_unknown(format!("value is too small to be represented by target ({}) and would become zero", r#type.mention_by_noun()))
+	}
 	fn report_number_fractional(&mut self) {
 		self.report_unknown(
 			"number cannot fit in target value due to having a fractional component")
 	}
 
+	fn report_duration_overflow(&mut self) {
+		self.report_unknown("duration is too large to be represented by target (a Duration)")
+	}
+
+	fn report_array_too_short(&mut self, expected: usize, actual: usize) {
+		self.report_unknown(format!("array is too short, expected {} elements, found {}", expected, actual))
+	}
+	fn report_array_too_long(&mut self, expected: usize, actual: usize) {
+		self.report_unknown(format!("array is too long, expected {} elements, found {}", expected, actual))
+	}
+
+	fn duplicates(&self) -> Duplicates {
+		Duplicates::Allow
+	}
+	fn report_duplicate_element(&mut self) {
+		self.report_unknown("duplicate element in set")
+	}
+	fn report_duplicate_key(&mut self) {
+		self.report_unknown("duplicate key in object")
+	}
+
+	fn report_key_unparsable(&mut self, expected: &str) {
+		self.report_unknown(format!("key cannot be parsed as {}", expected))
+	}
+	fn report_unknown_variant(&mut self, expected: &[&str]) {
+		self.report_unknown(format!("unknown variant, expected one of {:?}", expected))
+	}
+	fn report_variant_entries(&mut self, actual: usize) {
+		self.report_unknown(format!("expected an object with a single entry naming the variant, found {} entries", actual))
+	}
+
+	fn report_timestamp_invalid(&mut self, error: TimestampError) {
+		self.report_unknown(format!("invalid timestamp: {}", error))
+	}
+
 	fn report_missing_fields(&mut self) {
 		self.report_unknown("missing fields")
 	}
 
+	/// The value a pointer refers to is not in the document, the key of the
+	/// missing value having been pushed.
+	fn report_path_not_found(&mut self) {
+		self.report_unknown("path not found")
+	}
+
 	fn push_key(&mut self, _key: KeyKind<'s>) {}
 	fn pop_key(&mut self) {}
 }
@@ -268,3 +456,123 @@ impl<'s> ErrorContext<'s> for Stdout {
 			.expect("write failiure");
 	}
 }
+
+/// What went wrong in an [`Error`].
+#[derive(Debug)]
+pub enum ErrorKind {
+	Syntax(SyntaxError),
+	Semantic(String),
+	/// The value a pointer refers to is not in the document.
+	PathNotFound
+}
+
+impl Display for ErrorKind {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		match self {
+			Self::Syntax(error) => error.fmt(f),
+			Self::Semantic(message) => f.write_str(message),
+			Self::PathNotFound => f.write_str("path not found")
+		}
+	}
+}
+
+/// An error collected by [`Errors`], along with the path of the value it
+/// occurred at.
+#[derive(Debug)]
+pub struct Error<'s> {
+	pub kind: ErrorKind,
+	pub path: Vec<KeyKind<'s>>
+}
+
+impl Display for Error<'_> {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		self.kind.fmt(f)?;
+		if !self.path.is_empty() {
+			f.write_str(" at ")?;
+			for key in &self.path {
+				key.fmt(f)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl std::error::Error for Error<'_> {}
+
+/// Error context collecting every error reported, and the syntax error
+/// deserialization stopped at, if any.
+#[derive(Debug, Default)]
+pub struct Errors<'s> {
+	path: Vec<KeyKind<'s>>,
+	errors: Vec<Error<'s>>
+}
+
+impl<'s> Errors<'s> {
+	#[inline]
+	pub fn errors(&self) -> &[Error<'s>] {
+		&self.errors
+	}
+
+	#[inline]
+	pub fn into_errors(self) -> Vec<Error<'s>> {
+		self.errors
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.errors.is_empty()
+	}
+
+	/// Records a syntax error at the path deserialization stopped at.
+	pub fn push_syntax_error(&mut self, error: SyntaxError) {
+		self.errors.push(Error {kind: ErrorKind::Syntax(error), path: self.path.clone()});
+	}
+}
+
+impl<'s> ErrorContext<'s> for Errors<'s> {
+	fn report_unknown<M>(&mut self, message: M)
+			where M: ToString {
+		self.errors.push(Error {
+			kind: ErrorKind::Semantic(message.to_string()),
+			path: self.path.clone()
+		})
+	}
+
+	fn report_path_not_found(&mut self) {
+		self.errors.push(Error {kind: ErrorKind::PathNotFound, path: self.path.clone()});
+	}
+
+	#[inline]
+	fn push_key(&mut self, key: KeyKind<'s>) {
+		self.path.push(key);
+	}
+
+	#[inline]
+	fn pop_key(&mut self) {
+		self.path.pop();
+	}
+}
+
+impl<'s> IntoIterator for Errors<'s> {
+	type Item = Error<'s>;
+	type IntoIter = std::vec::IntoIter<Error<'s>>;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.errors.into_iter()
+	}
+}
+
+impl Display for Errors<'_> {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		for (index, error) in self.errors.iter().enumerate() {
+			if index != 0 {
+				f.write_str("\n")?;
+			}
+			error.fmt(f)?;
+		}
+		Ok(())
+	}
+}
+
+impl std::error::Error for Errors<'_> {}
@@ -1,10 +1,108 @@
 pub mod error;
 pub mod r#trait;
-
-use super::util::ShortOrLongRef;
-use self::{error::{ErrorContext, SyntaxError, JSONType}, r#trait::Deserialize};
+#[cfg(feature = "serde")]
+pub mod serde;
+
+use super::{pointer::{array_index, JsonPointer}, util::ShortOrLongRef};
+use self::{
+	error::{Duplicates, ErrorContext, Errors, KeyKind, SyntaxError, JSONType},
+	r#trait::{Deserialize, DeserializeSeed}
+};
 use std::{borrow::Cow, mem::{replace, forget}, ops::{Deref, DerefMut, Range}};
 
+/// Deserializes a value, failing with every error encountered if there were
+/// any.
+pub fn from_str<'s, T>(str: &'s str) -> Result<T, Errors<'s>>
+		where T: Deserialize<'s, Errors<'s>> {
+	let mut deserializer = Deserializer::new(str);
+	let mut errors = Errors::default();
+	let result = ValueDeserializer::new(&mut deserializer)
+		.and_then(|value| T::deserialize(value, &mut errors))
+		.and_then(|value| deserializer.finish().map(|()| value));
+	collect_errors(result, errors)
+}
+
+/// Deserializes only the value a pointer refers to, skipping over everything
+/// else. A missing value is reported as [`ErrorKind::PathNotFound`](error::ErrorKind::PathNotFound).
+pub fn from_str_at<'s, T>(str: &'s str, pointer: &JsonPointer) -> Result<T, Errors<'s>>
+		where T: Deserialize<'s, Errors<'s>> {
+	let mut deserializer = Deserializer::new(str);
+	let mut errors = Errors::default();
+	let result = ValueDeserializer::new(&mut deserializer)
+		.and_then(|value| deserialize_at(value, pointer.tokens(), &mut errors))
+		.and_then(|value| deserializer.finish().map(|()| value));
+	collect_errors(result, errors)
+}
+
+fn collect_errors<'s, T>(result: Result<Option<T>, SyntaxError>, mut errors: Errors<'s>)
+		-> Result<T, Errors<'s>> {
+	match result {
+		Ok(Some(value)) if errors.is_empty() => return Ok(value),
+		Ok(Some(_)) => (),
+		Ok(None) => if errors.is_empty() {
+			errors.report_unknown("invalid value");
+		},
+		Err(error) => errors.push_syntax_error(error)
+	}
+	Err(errors)
+}
+
+/// Deserializes the value at the end of a path of pointer tokens, walking
+/// along it without deserializing any of the values beside it. Of entries
+/// with the same key, the last one is used, as when deserializing a map.
+pub(crate) fn deserialize_at<'s, T, E>(value: ValueDeserializer<'_, 's>, tokens: &[String],
+		error_context: &mut E) -> Result<Option<T>, SyntaxError>
+			where T: Deserialize<'s, E>, E: ErrorContext<'s> {
+	let (token, rest) = match tokens.split_first() {
+		Some(split) => split,
+		None => return T::deserialize(value, error_context)
+	};
+
+	let key = match value {
+		ValueDeserializer::Object(mut object) => {
+			let mut found = None;
+			while let Some(entry) = object.next_entry()? {
+				let (name, value) = entry.accept()?;
+				if name == token.as_str() {
+					error_context.push_key(KeyKind::Object(name));
+					if found.is_some() && error_context.duplicates() == Duplicates::Report {
+						error_context.report_duplicate_key();
+					}
+					found = Some(deserialize_at(value, rest, error_context)?);
+					error_context.pop_key();
+				}
+			}
+			match found {
+				Some(result) => return Ok(result),
+				None => KeyKind::Object(Cow::Owned(token.clone()))
+			}
+		},
+		ValueDeserializer::Array(mut array) => match array_index(token) {
+			Some(index) => {
+				let mut current = 0;
+				while let Some(value) = array.next_entry()? {
+					if current == index {
+						error_context.push_key(KeyKind::Array(index));
+						let result = deserialize_at(value, rest, error_context);
+						error_context.pop_key();
+						return result
+					}
+					current += 1;
+				}
+				KeyKind::Array(index)
+			},
+			None => KeyKind::Object(Cow::Owned(token.clone()))
+		},
+		_ => KeyKind::Object(Cow::Owned(token.clone()))
+	};
+
+	error_context.push_key(key);
+	error_context.report_path_not_found();
+	error_context.pop_key();
+	Ok(None)
+}
+
+/// Deserializes a value, leaving whatever follows it unread.
 pub fn from_str_default<'s, T, E>(str: &'s str)
 		-> (Result<Option<T>, SyntaxError>, E)
 			where T: Deserialize<'s, E>, E: ErrorContext<'s> + Default + 's {
@@ -15,6 +113,17 @@ pub fn from_str_default<'s, T, E>(str: &'s str)
 	(result, error_context)
 }
 
+/// Deserializes a value with a seed, leaving whatever follows it unread.
+pub fn from_str_seed_default<'s, S, E>(seed: S, str: &'s str)
+		-> (Result<Option<S::Value>, SyntaxError>, E)
+			where S: DeserializeSeed<'s, E>, E: ErrorContext<'s> + Default + 's {
+	let mut deserializer = Deserializer::new(str);
+	let mut error_context = E::default();
+	let result = ValueDeserializer::new(&mut deserializer)
+		.and_then(|deserializer| seed.deserialize(deserializer, &mut error_context));
+	(result, error_context)
+}
+
 #[inline]
 fn copy_range<T>(range: &Range<T>) -> Range<T>
 		where T: Copy {
@@ -63,6 +172,11 @@ impl<'s> Number<'s> {
 	pub fn exponent(&self) -> Option<&str> {
 		self.exponent.as_ref().map(|exponent| &self.source[copy_range(exponent)])
 	}
+
+	#[inline]
+	pub fn into_owned(self) -> Number<'static> {
+		Number {source: Cow::Owned(self.source.into_owned()), ..self}
+	}
 }
 
 #[derive(Debug)]
@@ -127,6 +241,14 @@ impl<'s> Deserializer<'s> {
 		ShortOrLongRef::Long(&self.input[..self.index])
 	}
 
+	/// Checks that nothing but whitespace follows the value read.
+	pub fn finish(&mut self) -> Result<(), SyntaxError> {
+		let char = self.next_non_whitespace_char();
+		SyntaxError::expect(char, &[], true, self.consumed())?;
+		self.commit();
+		Ok(())
+	}
+
 	fn consumed(&self) -> usize {
 		self.index + self.consumed
 	}
@@ -221,14 +343,13 @@ impl<'d, 's> ObjectDeserializer<'d, 's>
 	}*/
 
 	#[inline(always)]
-	pub fn next_entry<'n>(&'n mut self)
-			-> Result<Option<ObjectFieldDeserializer<'n, 's>>, SyntaxError> {
+	fn advance(&mut self) -> Result<bool, SyntaxError> {
 		if replace(&mut self.past_first, true) { // ,"data":... or }
 			match self.deserializer.next_non_whitespace_char() {
 				Some('}') =>
-					{self.deserializer.reset(); Ok(None)},
+					{self.deserializer.reset(); Ok(false)},
 				Some(',') =>
-					Ok(Some(ObjectFieldDeserializer::new(self.deserializer.commit()))),
+					{self.deserializer.commit(); Ok(true)},
 
 				unexpected => {
 					self.deserializer.reset();
@@ -247,12 +368,33 @@ impl<'d, 's> ObjectDeserializer<'d, 's>
 
 			let char = self.deserializer.next_non_whitespace_char();
 			self.deserializer.reset();
-			if let Some('}') = char {
-				Ok(None)
-			} else {
-				Ok(Some(ObjectFieldDeserializer::new(self.deserializer)))
-			}
+			Ok(char != Some('}'))
+		}
+	}
+
+	#[inline(always)]
+	pub fn next_entry<'n>(&'n mut self)
+			-> Result<Option<ObjectFieldDeserializer<'n, 's>>, SyntaxError> {
+		Ok(self.advance()?.then(|| ObjectFieldDeserializer::new(self.deserializer)))
+	}
+
+	/// Reads the name of the next entry, leaving the deserializer at its value.
+	/// The value must be read with `next_value` before the next entry is.
+	pub fn next_name(&mut self) -> Result<Option<Cow<'s, str>>, SyntaxError> {
+		if !self.advance()? {
+			return Ok(None)
 		}
+
+		let name = StringDeserializer::new(self.deserializer).accept()?;
+		SyntaxError::expect(self.deserializer.next_non_whitespace_char(),
+			&[':'], false, self.deserializer.consumed())?;
+		self.deserializer.commit();
+		Ok(Some(name))
+	}
+
+	#[inline]
+	pub fn next_value(&mut self) -> Result<ValueDeserializer<'_, 's>, SyntaxError> {
+		ValueDeserializer::new(self.deserializer)
 	}
 }
 
@@ -272,7 +414,7 @@ impl<'d, 's> Deref for ObjectDeserializer<'d, 's>
 
 	#[inline]
 	fn deref(&self) -> &Self::Target {
-		&self.deserializer
+		self.deserializer
 	}
 }
 
@@ -280,7 +422,7 @@ impl<'d, 's> DerefMut for ObjectDeserializer<'d, 's>
 		where 's: 'd {
 	#[inline]
 	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.deserializer
+		self.deserializer
 	}
 }
 
@@ -316,9 +458,8 @@ impl<'d, 's> ObjectFieldDeserializer<'d, 's>
 
 impl<'d, 's> Drop for ObjectFieldDeserializer<'d, 's>
 		where 's: 'd {
-	fn drop<'u>(&'u mut self) {
+	fn drop(&mut self) {
 		let deserializer = self.0 as *mut _;
-		forget(self);
 		let _ = unsafe {Self::accept_internal(&mut *deserializer)};
 	}
 }
@@ -329,7 +470,7 @@ impl<'d, 's> Deref for ObjectFieldDeserializer<'d, 's>
 
 	#[inline]
 	fn deref(&self) -> &Self::Target {
-		&self.0
+		self.0
 	}
 }
 
@@ -337,7 +478,7 @@ impl<'d, 's> DerefMut for ObjectFieldDeserializer<'d, 's>
 		where 's: 'd {
 	#[inline]
 	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.0
+		self.0
 	}
 }
 
@@ -407,7 +548,7 @@ impl<'d, 's> Deref for ArrayDeserializer<'d, 's>
 
 	#[inline]
 	fn deref(&self) -> &Self::Target {
-		&self.deserializer
+		self.deserializer
 	}
 }
 
@@ -415,7 +556,7 @@ impl<'d, 's> DerefMut for ArrayDeserializer<'d, 's>
 		where 's: 'd {
 	#[inline]
 	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.deserializer
+		self.deserializer
 	}
 }
 
@@ -447,7 +588,10 @@ impl<'d, 's> StringDeserializer<'d, 's>
 		let result = loop {
 			match self.0.next_char() {
 				Some('\\') => {
-					let owned = owned.get_or_insert(String::from(&*self.0.buffer()));
+					let owned = owned.get_or_insert_with(|| {
+						let buffer = self.0.buffer();
+						String::from(&buffer[..buffer.len() - 1])
+					});
 
 					match self.0.next_char() {
 						Some(char @ ('"' | '\\' | '/')) =>
@@ -459,7 +603,10 @@ impl<'d, 's> StringDeserializer<'d, 's>
 						Some('r') => owned.push('\r'),
 						Some('t') => owned.push('\t'),
 
-						Some('u') => todo!(),
+						Some('u') => match self.unicode_escape() {
+							Ok(char) => owned.push(char),
+							Err(error) => break Err(error)
+						},
 
 						Some(escape) =>
 							break Err(SyntaxError::StringUnexpectedEscape(escape)),
@@ -484,6 +631,41 @@ impl<'d, 's> StringDeserializer<'d, 's>
 		self.0.commit();
 		result
 	}
+
+	fn hex_escape(&mut self) -> Result<u16, SyntaxError> {
+		let mut code = 0;
+		for _ in 0..4 {
+			let digit = self.0.next_char()
+				.ok_or(SyntaxError::StringUnterminated)?
+				.to_digit(16)
+				.ok_or(SyntaxError::StringInvalidUnicodeEscape)?;
+			code = code << 4 | digit as u16;
+		}
+		Ok(code)
+	}
+
+	/// Reads the four hex digits following `\u`, along with the low half of
+	/// a surrogate pair if the first one was a high half.
+	fn unicode_escape(&mut self) -> Result<char, SyntaxError> {
+		let high = self.hex_escape()?;
+		let code = match high {
+			0xD800..=0xDBFF => {
+				if (self.0.next_char(), self.0.next_char()) != (Some('\\'), Some('u')) {
+					return Err(SyntaxError::StringInvalidUnicodeEscape)
+				}
+
+				match self.hex_escape()? {
+					low @ 0xDC00..=0xDFFF =>
+						0x10000 + ((high as u32 - 0xD800) << 10 | (low as u32 - 0xDC00)),
+					_ => return Err(SyntaxError::StringInvalidUnicodeEscape)
+				}
+			},
+			0xDC00..=0xDFFF => return Err(SyntaxError::StringInvalidUnicodeEscape),
+			code => code as u32
+		};
+
+		char::from_u32(code).ok_or(SyntaxError::StringInvalidUnicodeEscape)
+	}
 }
 
 impl<'d, 's> Drop for StringDeserializer<'d, 's>
@@ -499,7 +681,7 @@ impl<'d, 's> Deref for StringDeserializer<'d, 's>
 
 	#[inline]
 	fn deref(&self) -> &Self::Target {
-		&self.0
+		self.0
 	}
 }
 
@@ -507,7 +689,7 @@ impl<'d, 's> DerefMut for StringDeserializer<'d, 's>
 		where 's: 'd {
 	#[inline]
 	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.0
+		self.0
 	}
 }
 
@@ -652,7 +834,7 @@ impl<'d, 's> Deref for NumberDeserializer<'d, 's>
 
 	#[inline]
 	fn deref(&self) -> &Self::Target {
-		&self.0
+		self.0
 	}
 }
 
@@ -660,6 +842,6 @@ impl<'d, 's> DerefMut for NumberDeserializer<'d, 's>
 		where 's: 'd {
 	#[inline]
 	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.0
+		self.0
 	}
 }
@@ -0,0 +1,442 @@
+//! Adapter letting types implementing serde's `Deserialize` be read by qj's
+//! parser.
+
+use super::{
+	error::{ErrorContext, KeyKind, SyntaxError},
+	r#trait::Deserialize,
+	ArrayDeserializer, Deserializer, Number, ObjectDeserializer, ValueDeserializer
+};
+use ::serde::{
+	de::{
+		self, value::CowStrDeserializer, DeserializeSeed, EnumAccess, MapAccess,
+		SeqAccess, VariantAccess, Visitor
+	},
+	forward_to_deserialize_any
+};
+use std::{borrow::Cow, fmt::{Display, Formatter, Result as FMTResult}};
+
+/// Error produced while deserializing through serde.
+#[derive(Debug)]
+pub enum Error {
+	Syntax(SyntaxError),
+	Custom {
+		message: String,
+		path: Vec<KeyKind<'static>>
+	}
+}
+
+impl Error {
+	#[inline]
+	fn at(mut self, key: KeyKind<'_>) -> Self {
+		if let Self::Custom {path, ..} = &mut self {
+			path.insert(0, key.into_static());
+		}
+		self
+	}
+}
+
+impl From<SyntaxError> for Error {
+	#[inline]
+	fn from(error: SyntaxError) -> Self {
+		Self::Syntax(error)
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		match self {
+			Self::Syntax(error) => error.fmt(f),
+			Self::Custom {message, path} => {
+				f.write_str(message)?;
+				if !path.is_empty() {
+					f.write_str(" at ")?;
+					for key in path {
+						key.fmt(f)?;
+					}
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+	#[inline]
+	fn custom<T>(message: T) -> Self
+			where T: Display {
+		Self::Custom {message: message.to_string(), path: Vec::new()}
+	}
+}
+
+/// Deserializes a serde type from a string.
+pub fn from_str<'s, T>(str: &'s str) -> Result<T, Error>
+		where T: de::Deserialize<'s> {
+	let mut deserializer = Deserializer::new(str);
+	let value = T::deserialize(ValueDeserializer::new(&mut deserializer)?)?;
+	deserializer.finish()?;
+	Ok(value)
+}
+
+/// Wrapper deserializing a serde type as part of a qj type, reporting serde's
+/// errors to the error context at the path they occurred at.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Serde<T>(pub T);
+
+impl<T> Serde<T> {
+	#[inline]
+	pub fn into_inner(self) -> T {
+		self.0
+	}
+}
+
+impl<'s, T, E> Deserialize<'s, E> for Serde<T>
+		where T: de::Deserialize<'s> + 's, E: ErrorContext<'s> {
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		match T::deserialize(value) {
+			Ok(value) => Ok(Some(Self(value))),
+			Err(Error::Syntax(error)) => Err(error),
+			Err(Error::Custom {message, path}) => {
+				let depth = path.len();
+				for key in path {
+					error_context.push_key(key);
+				}
+				error_context.report_unknown(message);
+				for _ in 0..depth {
+					error_context.pop_key();
+				}
+				Ok(None)
+			}
+		}
+	}
+}
+
+#[inline]
+fn visit_str<'s, V>(string: Cow<'s, str>, visitor: V) -> Result<V::Value, Error>
+		where V: Visitor<'s> {
+	match string {
+		Cow::Borrowed(string) => visitor.visit_borrowed_str(string),
+		Cow::Owned(string) => visitor.visit_string(string)
+	}
+}
+
+fn visit_number<'s, V>(number: Number<'s>, visitor: V) -> Result<V::Value, Error>
+		where V: Visitor<'s> {
+	if number.fraction.is_none() && number.exponent.is_none() {
+		if number.base_positive {
+			if let Ok(number) = number.source.parse() {
+				return visitor.visit_u64(number)
+			}
+		} else if let Ok(number) = number.source.parse() {
+			return visitor.visit_i64(number)
+		}
+	}
+
+	visitor.visit_f64(number.source.parse().expect("numbers are valid floats"))
+}
+
+impl<'d, 's> de::Deserializer<'s> for ValueDeserializer<'d, 's>
+		where 's: 'd {
+	type Error = Error;
+
+	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+			where V: Visitor<'s> {
+		match self {
+			Self::Object(object) => visitor.visit_map(ObjectAccess::new(object)),
+			Self::Array(array) => {
+				let mut access = ArrayAccess::new(array);
+				let value = visitor.visit_seq(&mut access)?;
+				access.finish()?;
+				Ok(value)
+			},
+			Self::String(string) => visit_str(string.accept()?, visitor),
+			Self::Number(number) => visit_number(number.accept()?, visitor),
+			Self::Boolean(boolean) => visitor.visit_bool(boolean),
+			Self::Null => visitor.visit_unit()
+		}
+	}
+
+	#[inline]
+	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+			where V: Visitor<'s> {
+		match self {
+			Self::Null => visitor.visit_none(),
+			value => visitor.visit_some(value)
+		}
+	}
+
+	#[inline]
+	fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V)
+			-> Result<V::Value, Self::Error>
+				where V: Visitor<'s> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	fn deserialize_enum<V>(self, _name: &'static str,
+			_variants: &'static [&'static str], visitor: V)
+				-> Result<V::Value, Self::Error>
+					where V: Visitor<'s> {
+		match self {
+			Self::String(string) =>
+				visitor.visit_enum(CowStrDeserializer::new(string.accept()?)),
+			Self::Object(object) => visitor.visit_enum(VariantObjectAccess(object)),
+			value => value.deserialize_any(visitor)
+		}
+	}
+
+	#[inline]
+	fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+			where V: Visitor<'s> {
+		drop(self);
+		visitor.visit_unit()
+	}
+
+	forward_to_deserialize_any! {
+		<W: Visitor<'s>>
+		bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+		bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+		identifier
+	}
+}
+
+struct ArrayAccess<'d, 's>
+		where 's: 'd {
+	array: ArrayDeserializer<'d, 's>,
+	index: usize
+}
+
+impl<'d, 's> ArrayAccess<'d, 's>
+		where 's: 'd {
+	#[inline]
+	fn new(array: ArrayDeserializer<'d, 's>) -> Self {
+		Self {array, index: 0}
+	}
+
+	#[inline]
+	fn finish(mut self) -> Result<(), Error> {
+		match self.array.next_entry()? {
+			Some(_) => Err(de::Error::invalid_length(self.index + 1, &"fewer elements")),
+			None => Ok(())
+		}
+	}
+}
+
+impl<'d, 's> SeqAccess<'s> for ArrayAccess<'d, 's>
+		where 's: 'd {
+	type Error = Error;
+
+	fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+			where T: DeserializeSeed<'s> {
+		let Some(value) = self.array.next_entry()? else {
+			return Ok(None)
+		};
+
+		let index = self.index;
+		self.index += 1;
+		seed.deserialize(value).map(Some).map_err(|error| error.at(KeyKind::Array(index)))
+	}
+}
+
+struct ObjectAccess<'d, 's>
+		where 's: 'd {
+	object: ObjectDeserializer<'d, 's>,
+	/// Name of the entry whose value has not been read yet.
+	pending: Option<Cow<'s, str>>
+}
+
+impl<'d, 's> ObjectAccess<'d, 's>
+		where 's: 'd {
+	#[inline]
+	fn new(object: ObjectDeserializer<'d, 's>) -> Self {
+		Self {object, pending: None}
+	}
+}
+
+impl<'d, 's> MapAccess<'s> for ObjectAccess<'d, 's>
+		where 's: 'd {
+	type Error = Error;
+
+	fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+			where K: DeserializeSeed<'s> {
+		if self.pending.take().is_some() {
+			drop(self.object.next_value()?);
+		}
+
+		let Some(name) = self.object.next_name()? else {
+			return Ok(None)
+		};
+
+		self.pending = Some(name.clone());
+		seed.deserialize(KeyDeserializer(name)).map(Some)
+	}
+
+	fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+			where V: DeserializeSeed<'s> {
+		let name = self.pending.take()
+			.expect("next_value_seed called before next_key_seed");
+		seed.deserialize(self.object.next_value()?)
+			.map_err(|error| error.at(KeyKind::Object(name)))
+	}
+}
+
+impl<'d, 's> Drop for ObjectAccess<'d, 's>
+		where 's: 'd {
+	#[inline]
+	fn drop(&mut self) {
+		if self.pending.take().is_some() {
+			let _ = self.object.next_value();
+		}
+	}
+}
+
+/// Externally tagged enum variant, `{"variant": value}`.
+struct VariantObjectAccess<'d, 's>(ObjectDeserializer<'d, 's>)
+	where 's: 'd;
+
+impl<'d, 's> EnumAccess<'s> for VariantObjectAccess<'d, 's>
+		where 's: 'd {
+	type Error = Error;
+	type Variant = VariantValueAccess<'d, 's>;
+
+	fn variant_seed<V>(mut self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+			where V: DeserializeSeed<'s> {
+		let Some(name) = self.0.next_name()? else {
+			return Err(de::Error::invalid_length(0, &"an object with a single entry"))
+		};
+
+		let variant = seed.deserialize(KeyDeserializer(name.clone()))?;
+		Ok((variant, VariantValueAccess {object: self.0, name: Some(name)}))
+	}
+}
+
+struct VariantValueAccess<'d, 's>
+		where 's: 'd {
+	object: ObjectDeserializer<'d, 's>,
+	name: Option<Cow<'s, str>>
+}
+
+impl<'d, 's> VariantValueAccess<'d, 's>
+		where 's: 'd {
+	fn value<T, F>(mut self, f: F) -> Result<T, Error>
+			where F: FnOnce(ValueDeserializer<'_, 's>) -> Result<T, Error> {
+		let name = self.name.take().expect("variant value read twice");
+		let value = f(self.object.next_value()?)
+			.map_err(|error| error.at(KeyKind::Object(name)))?;
+
+		match self.object.next_name()? {
+			Some(_) => {
+				drop(self.object.next_value()?);
+				Err(de::Error::invalid_length(2, &"an object with a single entry"))
+			},
+			None => Ok(value)
+		}
+	}
+}
+
+impl<'d, 's> VariantAccess<'s> for VariantValueAccess<'d, 's>
+		where 's: 'd {
+	type Error = Error;
+
+	#[inline]
+	fn unit_variant(self) -> Result<(), Self::Error> {
+		self.value(|value| de::Deserialize::deserialize(value))
+	}
+
+	#[inline]
+	fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+			where T: DeserializeSeed<'s> {
+		self.value(|value| seed.deserialize(value))
+	}
+
+	#[inline]
+	fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+			where V: Visitor<'s> {
+		self.value(|value| de::Deserializer::deserialize_seq(value, visitor))
+	}
+
+	#[inline]
+	fn struct_variant<V>(self, _fields: &'static [&'static str], visitor: V)
+			-> Result<V::Value, Self::Error>
+				where V: Visitor<'s> {
+		self.value(|value| de::Deserializer::deserialize_map(value, visitor))
+	}
+}
+
+impl<'d, 's> Drop for VariantValueAccess<'d, 's>
+		where 's: 'd {
+	#[inline]
+	fn drop(&mut self) {
+		if self.name.take().is_some() {
+			let _ = self.object.next_value();
+		}
+	}
+}
+
+/// Deserializer for object keys, which parses numbers and booleans out of the
+/// key when asked to.
+struct KeyDeserializer<'s>(Cow<'s, str>);
+
+macro_rules! deserialize_parsed_key {
+	($($method:ident: $visit:ident),*) => {
+		$(
+			fn $method<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+					where V: Visitor<'s> {
+				match self.0.parse() {
+					Ok(key) => visitor.$visit(key),
+					Err(_) => Err(de::Error::invalid_value(
+						de::Unexpected::Str(&self.0), &visitor))
+				}
+			}
+		)*
+	}
+}
+
+impl<'s> de::Deserializer<'s> for KeyDeserializer<'s> {
+	type Error = Error;
+
+	#[inline]
+	fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+			where V: Visitor<'s> {
+		visit_str(self.0, visitor)
+	}
+
+	deserialize_parsed_key! {
+		deserialize_bool: visit_bool,
+		deserialize_i8: visit_i8, deserialize_i16: visit_i16,
+		deserialize_i32: visit_i32, deserialize_i64: visit_i64,
+		deserialize_i128: visit_i128,
+		deserialize_u8: visit_u8, deserialize_u16: visit_u16,
+		deserialize_u32: visit_u32, deserialize_u64: visit_u64,
+		deserialize_u128: visit_u128,
+		deserialize_f32: visit_f32, deserialize_f64: visit_f64
+	}
+
+	#[inline]
+	fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+			where V: Visitor<'s> {
+		visitor.visit_some(self)
+	}
+
+	#[inline]
+	fn deserialize_newtype_struct<V>(self, _name: &'static str, visitor: V)
+			-> Result<V::Value, Self::Error>
+				where V: Visitor<'s> {
+		visitor.visit_newtype_struct(self)
+	}
+
+	#[inline]
+	fn deserialize_enum<V>(self, _name: &'static str,
+			_variants: &'static [&'static str], visitor: V)
+				-> Result<V::Value, Self::Error>
+					where V: Visitor<'s> {
+		visitor.visit_enum(CowStrDeserializer::new(self.0))
+	}
+
+	forward_to_deserialize_any! {
+		<W: Visitor<'s>>
+		char str string bytes byte_buf unit unit_struct seq tuple tuple_struct
+		map struct identifier ignored_any
+	}
+}
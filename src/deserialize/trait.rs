@@ -1,21 +1,142 @@
 use super::{
-	error::{AssociatedNumeric, ErrorContext, JSONType, KeyKind, SyntaxError},
-	Number, ValueDeserializer
+	error::{
+		AssociatedNumeric, Coercion, Duplicates, ErrorContext, FloatPrimitive, FloatRange,
+		JSONType, KeyKind, SyntaxError
+	},
+	ArrayDeserializer, Deserializer, Number, ValueDeserializer
+};
+use std::{
+	borrow::Cow,
+	cell::{Cell, RefCell},
+	collections::{
+		BinaryHeap, BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque
+	},
+	ffi::OsString,
+	fmt::Display,
+	hash::{BuildHasher, Hash},
+	marker::PhantomData,
+	net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+	num::{
+		NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+		NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+		Wrapping
+	},
+	path::PathBuf,
+	rc::Rc,
+	str::FromStr,
+	sync::{Arc, Mutex, RwLock},
+	time::Duration
 };
-use std::{borrow::Cow, collections::HashMap, hash::Hash};
 
-pub use qj_derive::Deserialize;
+pub use qj_derive::{Deserialize, DeserializeKey};
 
 pub trait Deserialize<'s, E>: 's
 		where E: ErrorContext<'s> {
 	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
 		-> Result<Option<Self>, SyntaxError> where Self: Sized;
+
+	/// Deserializes over an existing value, reusing its allocations where the
+	/// implementation is able to. Returns whether deserialization succeeded;
+	/// if it didn't, `self` is left valid but unspecified.
+	#[inline]
+	fn deserialize_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+			error_context: &mut E) -> Result<bool, SyntaxError> where Self: Sized {
+		Ok(match Self::deserialize(value, error_context)? {
+			Some(value) => {*self = value; true},
+			None => false
+		})
+	}
+}
+
+/// What a collection does when one of its elements fails to deserialize, the
+/// failure itself having already been reported.
+#[derive(Debug)]
+pub enum ElementFailure<T> {
+	/// The element is left out of the collection. This is what
+	/// [`Deserialize`] implementations of collections do.
+	Drop,
+	/// The whole collection fails.
+	Fail,
+	/// The element is replaced with the value returned by the function, such
+	/// as [`Default::default`].
+	Substitute(fn() -> T)
+}
+
+impl<T> Clone for ElementFailure<T> {
+	#[inline]
+	fn clone(&self) -> Self {
+		*self
+	}
+}
+
+impl<T> Copy for ElementFailure<T> {}
+
+/// A collection whose handling of elements that fail to deserialize can be
+/// chosen, rather than always leaving them out.
+pub trait DeserializeElements<'s, E>: Deserialize<'s, E>
+		where E: ErrorContext<'s> {
+	/// The type of the elements, or of the values for maps.
+	type Element;
+
+	fn deserialize_elements<'d>(value: ValueDeserializer<'d, 's>,
+		error_context: &mut E, on_failure: ElementFailure<Self::Element>)
+			-> Result<Option<Self>, SyntaxError> where Self: Sized;
+
+	/// Deserializes over an existing collection as
+	/// [`deserialize_in_place`](Deserialize::deserialize_in_place) does.
+	#[inline]
+	fn deserialize_elements_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+			error_context: &mut E, on_failure: ElementFailure<Self::Element>)
+				-> Result<bool, SyntaxError> where Self: Sized {
+		Ok(match Self::deserialize_elements(value, error_context, on_failure)? {
+			Some(value) => {*self = value; true},
+			None => false
+		})
+	}
+}
+
+/// Stateful deserialization, for when nested values need runtime context
+/// such as a string interner or a schema registry. The seed is consumed to
+/// produce a [`Value`](Self::Value); to pass it on to nested values, seeds
+/// are typically cheap to clone, such as references.
+///
+/// Any [`Deserialize`] type can be used as a seed through [`PhantomData`].
+pub trait DeserializeSeed<'s, E>
+		where E: ErrorContext<'s> {
+	type Value;
+
+	fn deserialize<'d>(self, value: ValueDeserializer<'d, 's>, error_context: &mut E)
+		-> Result<Option<Self::Value>, SyntaxError>;
+}
+
+/// A type that can be parsed from the name of an object entry, such as the
+/// keys of a map. It can be derived for enums without fields, whose
+/// variants are parsed from their names.
+pub trait DeserializeKey<'s, E>: 's
+		where E: ErrorContext<'s> {
+	fn deserialize_key(key: Cow<'s, str>, error_context: &mut E) -> Option<Self>
+		where Self: Sized;
+}
+
+/// Deserializes any type implementing [`FromStr`] from a JSON string,
+/// reporting the error message if it cannot be parsed.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct FromStrValue<T>(pub T);
+
+impl<T> FromStrValue<T> {
+	#[inline]
+	pub fn into_inner(self) -> T {
+		self.0
+	}
 }
 
 macro_rules! deserializer_match {
-	(in $value:expr, $error:expr; $($type:ident($bind:pat) => $action:expr),*) => {
+	(in $value:expr, $error:expr; $($type:ident($bind:pat) => $action:expr),*
+			$(; coerce $($coerced:ident($coerced_bind:pat) => $coerced_action:expr),*)?) => {
 		match $value {
 			$(ValueDeserializer::$type($bind) => $action,)*
+			$($(ValueDeserializer::$coerced($coerced_bind)
+				if $error.coercion() == Coercion::Lenient => $coerced_action,)*)?
 			unexpected => {
 				$error
 					.report_unexpected_type(unexpected.kind(), &[$(JSONType::$type),*]);
@@ -28,7 +149,7 @@ macro_rules! deserializer_match {
 macro_rules! number_to_int {
 	($number:expr, $error_context:expr, $int:ty) => {
 		{
-			let float = number_to_float($number.accept()?);
+			let float = number_to_float($number);
 			let error_context = $error_context;
 			if float.round() != float {
 				error_context.report_number_fractional();
@@ -57,37 +178,357 @@ macro_rules! impl_integers {
 				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
 						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
 					deserializer_match! {in value, error_context;
-						Number(number) => number_to_int!(number, error_context, $integer)
+						Number(number) =>
+							number_to_int!(number.accept()?, error_context, $integer);
+						coerce
+						String(string) => match parse_number(&string.accept()?) {
+							Some(number) => {
+								error_context.report_coerced(JSONType::String, JSONType::Number);
+								number_to_int!(number, error_context, $integer)
+							},
+							None => Ok(coerce_failed(JSONType::String, JSONType::Number,
+								error_context))
+						}
+					}
+				}
+			}
+
+			impl<'s, E> DeserializeKey<'s, E> for $integer
+					where E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize_key(key: Cow<'s, str>, error_context: &mut E)
+						-> Option<Self> {
+					let integer = key.parse().ok();
+					if integer.is_none() {
+						error_context.report_key_unparsable(
+							<$integer>::NUMERIC_PRIMITIVE.mention_by_noun());
 					}
+					integer
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_non_zero_integers {
+	($($non_zero:ty: $integer:ty),*) => {
+		$(
+			impl<'s, E> Deserialize<'s, E> for $non_zero
+					where E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					Ok(<$integer>::deserialize(value, error_context)?
+						.and_then(|integer| {
+							let non_zero = <$non_zero>::new(integer);
+							if non_zero.is_none() {
+								error_context.report_number_zero(<$integer>::NUMERIC_PRIMITIVE);
+							}
+							non_zero
+						}))
 				}
 			}
 		)*
 	}
 }
 
+macro_rules! number_to_float {
+	($number:expr, $error_context:expr, $float:ident: $primitive:ident) => {
+		{
+			let number = $number;
+			let error_context = $error_context;
+			let float: $float = number.source.parse()
+				.expect("number was parsed incorrectly");
+			if float.is_infinite() {
+				match error_context.float_range() {
+					FloatRange::Error => {
+						error_context.report_float_overflow(FloatPrimitive::$primitive);
+						None
+					},
+					FloatRange::Saturate => Some($float::MAX.copysign(float)),
+					FloatRange::Accept => Some(float)
+				}
+			} else if float == 0. && !number_is_zero(&number) {
+				match error_context.float_range() {
+					FloatRange::Error => {
+						error_context.report_float_underflow(FloatPrimitive::$primitive);
+						None
+					},
+					FloatRange::Saturate | FloatRange::Accept => Some(float)
+				}
+			} else {
+				Some(float)
+			}
+		}
+	}
+}
+
+macro_rules! impl_floats {
+	($($float:ident: $primitive:ident),*) => {
+		$(
+			impl<'s, E> Deserialize<'s, E> for $float
+					where E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					deserializer_match! {in value, error_context;
+						Number(number) => Ok(number_to_float!(number.accept()?,
+							error_context, $float: $primitive));
+						coerce
+						String(string) => match parse_number(&string.accept()?) {
+							Some(number) => {
+								error_context.report_coerced(JSONType::String, JSONType::Number);
+								Ok(number_to_float!(number, error_context, $float: $primitive))
+							},
+							None => Ok(coerce_failed(JSONType::String, JSONType::Number,
+								error_context))
+						}
+					}
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_tuples {
+	(@impl $($index:tt $type:ident $variable:ident)+) => {
+		impl<'s, $($type,)+ E> Deserialize<'s, E> for ($($type,)+)
+				where $($type: Deserialize<'s, E>,)+ E: ErrorContext<'s> {
+			#[inline]
+			fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+					error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+				const LENGTH: usize = [$($index),+].len();
+
+				deserializer_match! {in value, error_context;
+					Array(mut array) => {
+						$(
+							let $variable = match array.next_entry()? {
+								Some(value) => {
+									error_context.push_key(KeyKind::Array($index));
+									let value = $type::deserialize(value, error_context)?;
+									error_context.pop_key();
+									value
+								},
+								None => {
+									error_context.report_array_too_short(LENGTH, $index);
+									return Ok(None)
+								}
+							};
+						)+
+
+						if !finish_array(array, LENGTH, error_context)? {
+							return Ok(None)
+						}
+
+						match ($($variable,)+) {
+							($(Some($variable),)+) => Ok(Some(($($variable,)+))),
+							_ => Ok(None)
+						}
+					}
+				}
+			}
+		}
+	};
+
+	([$($done:tt)*] $index:tt $type:ident $variable:ident $($rest:tt)*) => {
+		impl_tuples!(@impl $($done)* $index $type $variable);
+		impl_tuples!([$($done)* $index $type $variable] $($rest)*);
+	};
+
+	([$($done:tt)*]) => {}
+}
+
 #[inline]
 fn number_to_float(number: Number) -> f64 {
-	let mut result = number.source[number.base].parse()
-		.expect("number base was parsed incorrectly");
-	let fraction = number.fraction
-		.map(|fraction| {
-			number.source[fraction.start - 1..fraction.end].parse()
-				.expect("number fraction was parsed incorrectly")
-		})
-		.unwrap_or(0.);
-	let mut exponent = number.exponent
-		.map(|exponent| {
-			number.source[exponent].parse()
-				.expect("number fraction was parsed incorrectly")
-		})
-		.unwrap_or(0.);
+	number.source.parse().expect("number was parsed incorrectly")
+}
+
+/// Whether every digit of the number is zero, as opposed to it having been
+/// rounded to zero.
+#[inline]
+fn number_is_zero(number: &Number) -> bool {
+	number.base().bytes()
+		.chain(number.fraction().unwrap_or_default().bytes())
+		.all(|digit| digit == b'0')
+}
+
+macro_rules! impl_from_str {
+	($($type:ty),*) => {
+		$(
+			impl<'s, E> Deserialize<'s, E> for $type
+					where E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					Ok(FromStrValue::deserialize(value, error_context)?
+						.map(FromStrValue::into_inner))
+				}
+			}
+
+			impl<'s, E> DeserializeKey<'s, E> for $type
+					where E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize_key(key: Cow<'s, str>, error_context: &mut E)
+						-> Option<Self> {
+					parse_str(&key, error_context)
+				}
+			}
+		)*
+	}
+}
+
+/// Consumes what remains of an array expected to hold `expected` elements,
+/// reporting it if any elements remain. Returns whether the array was the
+/// expected length.
+fn finish_array<'s, E>(mut array: ArrayDeserializer<'_, 's>, expected: usize,
+		error_context: &mut E) -> Result<bool, SyntaxError>
+			where E: ErrorContext<'s> {
+	let mut actual = expected;
+	while array.next_entry()?.is_some() {
+		actual += 1;
+	}
 
-	if !number.base_positive {result *= -1.}
-	if !number.exponent_positive {exponent *= -1.}
+	if actual != expected {
+		error_context.report_array_too_long(expected, actual);
+	}
+	Ok(actual == expected)
+}
 
-	result += fraction;
-	result *= f64::powf(10., exponent);
-	result
+/// Parses a string through [`FromStr`], reporting the error message if it
+/// cannot be parsed.
+#[inline]
+fn parse_str<'s, T, E>(string: &str, error_context: &mut E) -> Option<T>
+		where T: FromStr, T::Err: Display, E: ErrorContext<'s> {
+	string.parse()
+		.map_err(|error| error_context.report_string_unparsable(error))
+		.ok()
+}
+
+/// Why a string is not a duration.
+enum DurationError {
+	Malformed(&'static str),
+	/// The duration is too long to be represented.
+	Overflow
+}
+
+/// Parses an ISO 8601 duration such as `PT1H30M` or `P1DT0.5S`. Years and
+/// months are rejected, having no fixed length, components have to be in
+/// order without repeating, and only the last component may have a fraction.
+fn parse_iso_duration(string: &str) -> Result<Duration, DurationError> {
+	const NANOS_PER_SECOND: u128 = 1_000_000_000;
+
+	let mut rest = string.strip_prefix('P')
+		.ok_or(DurationError::Malformed("expected a duration starting with 'P'"))?;
+	let mut nanos: u128 = 0;
+	let mut time = false;
+	let mut components = 0;
+	// Rank of the last designator, which the next one has to exceed.
+	let mut rank = 0;
+	let mut fractional = false;
+
+	while !rest.is_empty() {
+		if let Some(after) = rest.strip_prefix('T') {
+			if time {
+				return Err(DurationError::Malformed("'T' can only appear once"))
+			}
+			time = true;
+			components = 0;
+			rest = after;
+			continue
+		}
+		if fractional {
+			return Err(DurationError::Malformed("only the last component can have a fraction"))
+		}
+
+		let number_end = rest
+			.find(|char: char| !matches!(char, '0'..='9' | '.' | ','))
+			.ok_or(DurationError::Malformed("expected a designator after a number"))?;
+		let (number, after) = rest.split_at(number_end);
+		let designator = after.chars().next()
+			.expect("designator was found incorrectly");
+		rest = &after[designator.len_utf8()..];
+
+		let (unit, designator_rank) = match (time, designator) {
+			(false, 'W') => (604_800 * NANOS_PER_SECOND, 1),
+			(false, 'D') => (86_400 * NANOS_PER_SECOND, 2),
+			(false, 'Y' | 'M') =>
+				return Err(DurationError::Malformed("years and months have no fixed length")),
+			(true, 'H') => (3_600 * NANOS_PER_SECOND, 3),
+			(true, 'M') => (60 * NANOS_PER_SECOND, 4),
+			(true, 'S') => (NANOS_PER_SECOND, 5),
+			_ => return Err(DurationError::Malformed("unexpected designator"))
+		};
+		if designator_rank <= rank {
+			return Err(DurationError::Malformed("components are out of order or repeated"))
+		}
+		rank = designator_rank;
+
+		let (whole, fraction) = match number.split_once(['.', ',']) {
+			Some((whole, fraction)) => (whole, Some(fraction)),
+			None => (number, None)
+		};
+		let malformed = whole.is_empty() || fraction.is_some_and(|fraction|
+			fraction.is_empty() || fraction.contains(['.', ',']));
+		if malformed {
+			return Err(DurationError::Malformed("malformed number"))
+		}
+
+		let whole: u128 = whole.parse().map_err(|_| DurationError::Overflow)?;
+		nanos = whole.checked_mul(unit)
+			.and_then(|whole| nanos.checked_add(whole))
+			.ok_or(DurationError::Overflow)?;
+		if let Some(fraction) = fraction {
+			let fraction = &fraction[..fraction.len().min(18)];
+			let digits = fraction.parse::<u128>()
+				.expect("fraction was parsed incorrectly");
+			nanos = nanos.checked_add(digits * unit / 10u128.pow(fraction.len() as u32))
+				.ok_or(DurationError::Overflow)?;
+			fractional = true;
+		}
+		components += 1;
+	}
+
+	if components == 0 {
+		return Err(DurationError::Malformed("expected at least one component"))
+	}
+	u64::try_from(nanos / NANOS_PER_SECOND)
+		.map(|seconds| Duration::new(seconds,
+			(nanos % NANOS_PER_SECOND) as u32))
+		.map_err(|_| DurationError::Overflow)
+}
+
+/// Parses a string holding nothing but a JSON number, for lenient coercion.
+fn parse_number(string: &str) -> Option<Number<'_>> {
+	let mut deserializer = Deserializer::new(string);
+	let number = match ValueDeserializer::new(&mut deserializer).ok()? {
+		ValueDeserializer::Number(number) => number.accept().ok()?,
+		_ => return None
+	};
+	deserializer.next_non_whitespace_char().is_none().then_some(number)
+}
+
+/// Reports a value of a coercible type that couldn't be converted, returning
+/// `None` for convenience.
+#[inline]
+fn coerce_failed<'s, T, E>(found: JSONType, expected: JSONType,
+		error_context: &mut E) -> Option<T>
+			where E: ErrorContext<'s> {
+	error_context.report_unexpected_type(found, &[expected]);
+	None
+}
+
+/// Reports a successful coercion, or a failed one if `value` is `None`.
+#[inline]
+fn coerced<'s, T, E>(found: JSONType, expected: JSONType, value: Option<T>,
+		error_context: &mut E) -> Option<T>
+			where E: ErrorContext<'s> {
+	match value {
+		Some(value) => {
+			error_context.report_coerced(found, expected);
+			Some(value)
+		},
+		None => coerce_failed(found, expected, error_context)
+	}
 }
 
 impl<'s, E> Deserialize<'s, E> for String
@@ -95,9 +536,22 @@ impl<'s, E> Deserialize<'s, E> for String
 	#[inline]
 	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
 			-> Result<Option<Self>, SyntaxError> {
-		Cow::deserialize(value, error_context)
+		Cow::<str>::deserialize(value, error_context)
 			.map(|value| value.map(Cow::into_owned))
 	}
+
+	#[inline]
+	fn deserialize_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+			error_context: &mut E) -> Result<bool, SyntaxError> {
+		Ok(match Cow::<str>::deserialize(value, error_context)? {
+			Some(string) => {
+				self.clear();
+				self.push_str(&string);
+				true
+			},
+			None => false
+		})
+	}
 }
 
 impl<'s, E> Deserialize<'s, E> for Cow<'s, str>
@@ -106,7 +560,43 @@ impl<'s, E> Deserialize<'s, E> for Cow<'s, str>
 	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
 			-> Result<Option<Self>, SyntaxError> {
 		deserializer_match! {in value, error_context;
-			String(string) => Ok(Some(string.accept()?))
+			String(string) => Ok(Some(string.accept()?));
+			coerce
+			Number(number) => Ok(coerced(JSONType::Number, JSONType::String,
+				Some(number.accept()?.source), error_context)),
+			Boolean(boolean) => Ok(coerced(JSONType::Boolean, JSONType::String,
+				Some(Cow::Borrowed(if boolean {"true"} else {"false"})), error_context))
+		}
+	}
+}
+
+impl<'s, E> Deserialize<'s, E> for Box<str>
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		String::deserialize(value, error_context)
+			.map(|value| value.map(String::into_boxed_str))
+	}
+}
+
+impl<'s, E> Deserialize<'s, E> for char
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		deserializer_match! {in value, error_context;
+			String(string) => {
+				let string = string.accept()?;
+				let mut chars = string.chars();
+				match (chars.next(), chars.next()) {
+					(Some(char), None) => Ok(Some(char)),
+					_ => {
+						error_context.report_string_expected_char();
+						Ok(None)
+					}
+				}
+			}
 		}
 	}
 }
@@ -133,30 +623,236 @@ impl_integers! {
 	isize, i8, i16, i32, i64, i128
 }
 
-impl<'s, E> Deserialize<'s, E> for f32
+impl_non_zero_integers! {
+	NonZeroUsize: usize, NonZeroU8: u8, NonZeroU16: u16, NonZeroU32: u32,
+	NonZeroU64: u64, NonZeroU128: u128,
+	NonZeroIsize: isize, NonZeroI8: i8, NonZeroI16: i16, NonZeroI32: i32,
+	NonZeroI64: i64, NonZeroI128: i128
+}
+
+impl_floats! {
+	f32: F32,
+	f64: F64
+}
+
+impl<'s, T, E> Deserialize<'s, E> for FromStrValue<T>
+		where T: FromStr + 's, T::Err: Display, E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		deserializer_match! {in value, error_context;
+			String(string) =>
+				Ok(parse_str(&string.accept()?, error_context).map(FromStrValue))
+		}
+	}
+}
+
+impl<'s, T, E> DeserializeKey<'s, E> for FromStrValue<T>
+		where T: FromStr + 's, T::Err: Display, E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, error_context: &mut E) -> Option<Self> {
+		parse_str(&key, error_context).map(FromStrValue)
+	}
+}
+
+impl_from_str! {
+	IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6
+}
+
+impl<'s, E> Deserialize<'s, E> for PathBuf
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		Ok(String::deserialize(value, error_context)?.map(PathBuf::from))
+	}
+}
+
+impl<'s, E> Deserialize<'s, E> for OsString
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		Ok(String::deserialize(value, error_context)?.map(OsString::from))
+	}
+}
+
+impl<'s, E> Deserialize<'s, E> for Duration
 		where E: ErrorContext<'s> {
 	#[inline]
 	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
 			-> Result<Option<Self>, SyntaxError> {
 		deserializer_match! {in value, error_context;
-			Number(number) =>
-				Ok(Some(number_to_float(number.accept()?) as f32))
+			Object(mut object) => {
+				let mut seconds = None;
+				let mut nanos = None;
+				while let Some(entry) = object.next_entry()? {
+					let (name, value) = entry.accept()?;
+					error_context.push_key(KeyKind::Object(name.clone()));
+					match name.as_ref() {
+						"secs" => seconds = Some(u64::deserialize(value, error_context)?),
+						"nanos" => nanos = Some(u32::deserialize(value, error_context)?),
+						_ => ()
+					}
+					error_context.pop_key();
+				}
+
+				match (seconds, nanos) {
+					(Some(Some(seconds)), Some(Some(nanos))) => {
+						let duration = Duration::from_secs(seconds)
+							.checked_add(Duration::from_nanos(nanos.into()));
+						if duration.is_none() {
+							error_context.report_duration_overflow();
+						}
+						Ok(duration)
+					},
+					(Some(_), Some(_)) => Ok(None),
+					_ => {
+						error_context.report_missing_fields();
+						Ok(None)
+					}
+				}
+			},
+			String(string) => Ok(parse_iso_duration(&string.accept()?)
+				.map_err(|error| match error {
+					DurationError::Malformed(error) => error_context.report_string_unparsable(error),
+					DurationError::Overflow => error_context.report_duration_overflow()
+				})
+				.ok())
 		}
 	}
 }
 
-impl<'s, E> Deserialize<'s, E> for f64
+impl<'s, E> Deserialize<'s, E> for bool
 		where E: ErrorContext<'s> {
 	#[inline]
 	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
 			-> Result<Option<Self>, SyntaxError> {
 		deserializer_match! {in value, error_context;
-			Number(number) =>
-				Ok(Some(number_to_float(number.accept()?)))
+			Boolean(boolean) => Ok(Some(boolean));
+			coerce
+			String(string) => {
+				let boolean = match &*string.accept()? {
+					"true" => Some(true),
+					"false" => Some(false),
+					_ => None
+				};
+				Ok(coerced(JSONType::String, JSONType::Boolean, boolean, error_context))
+			},
+			Number(number) => {
+				let boolean = match number_to_float(number.accept()?) {
+					1. => Some(true),
+					0. => Some(false),
+					_ => None
+				};
+				Ok(coerced(JSONType::Number, JSONType::Boolean, boolean, error_context))
+			}
+		}
+	}
+}
+
+impl<'s, E> Deserialize<'s, E> for ()
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		match value {
+			ValueDeserializer::Null => Ok(Some(())),
+			unexpected => {
+				error_context.report_unexpected_type(unexpected.kind(), &[JSONType::Null]);
+				Ok(None)
+			}
 		}
 	}
 }
 
+impl<'s, T, E> Deserialize<'s, E> for PhantomData<T>
+		where T: ?Sized + 's, E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		Ok(<()>::deserialize(value, error_context)?.map(|_| PhantomData))
+	}
+}
+
+macro_rules! impl_wrappers {
+	($($wrapper:ident<T> => $new:expr),*) => {
+		$(
+			impl<'s, T, E> Deserialize<'s, E> for $wrapper<T>
+					where T: Deserialize<'s, E>, E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					Ok(T::deserialize(value, error_context)?.map($new))
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_shared_slices {
+	($($pointer:ident),*) => {
+		$(
+			/// Strings without escapes are copied straight from the input.
+			/// Escaped ones are unescaped into a `String` first and copied
+			/// once more, as the pointer keeps its counts in the same
+			/// allocation as the text.
+			impl<'s, E> Deserialize<'s, E> for $pointer<str>
+					where E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					Ok(Cow::<str>::deserialize(value, error_context)?
+						.map(|string| match string {
+							Cow::Borrowed(string) => $pointer::from(string),
+							Cow::Owned(string) => $pointer::from(string)
+						}))
+				}
+			}
+
+			impl<'s, T, E> Deserialize<'s, E> for $pointer<[T]>
+					where T: Deserialize<'s, E>, E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					Ok(Vec::deserialize(value, error_context)?.map($pointer::from))
+				}
+			}
+		)*
+	}
+}
+
+impl_wrappers! {
+	Wrapping<T> => Wrapping,
+	Box<T> => Box::new,
+	Rc<T> => Rc::new,
+	Arc<T> => Arc::new,
+	Cell<T> => Cell::new,
+	RefCell<T> => RefCell::new,
+	Mutex<T> => Mutex::new,
+	RwLock<T> => RwLock::new
+}
+
+impl_shared_slices! {Rc, Arc}
+
+impl<'s, T, E> Deserialize<'s, E> for Box<[T]>
+		where T: Deserialize<'s, E>, E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		Ok(Vec::deserialize(value, error_context)?.map(Vec::into_boxed_slice))
+	}
+}
+
+impl<'s, T, E> Deserialize<'s, E> for Cow<'s, [T]>
+		where T: Deserialize<'s, E> + Clone, E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		Ok(Vec::deserialize(value, error_context)?.map(Cow::Owned))
+	}
+}
+
 impl<'s, T, E> Deserialize<'s, E> for Option<T>
 		where T: Deserialize<'s, E>, E: ErrorContext<'s> {
 	#[inline]
@@ -167,6 +863,175 @@ impl<'s, T, E> Deserialize<'s, E> for Option<T>
 			value => Ok(T::deserialize(value, error_context)?.map(Some))
 		}
 	}
+
+	#[inline]
+	fn deserialize_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+			error_context: &mut E) -> Result<bool, SyntaxError> {
+		match (self, value) {
+			(this, ValueDeserializer::Null) => {*this = None; Ok(true)},
+			(Some(this), value) => this.deserialize_in_place(value, error_context),
+			(this, value) => Ok(match T::deserialize(value, error_context)? {
+				Some(value) => {*this = Some(value); true},
+				None => false
+			})
+		}
+	}
+}
+
+macro_rules! impl_sequences {
+	($($sequence:ident<T> where $($bound:ident),* => $insert:expr);*) => {
+		$(
+			impl<'s, T, E> Deserialize<'s, E> for $sequence<T>
+					where T: Deserialize<'s, E> $(+ $bound)*, E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					Self::deserialize_elements(value, error_context, ElementFailure::Drop)
+				}
+			}
+
+			impl<'s, T, E> DeserializeElements<'s, E> for $sequence<T>
+					where T: Deserialize<'s, E> $(+ $bound)*, E: ErrorContext<'s> {
+				type Element = T;
+
+				#[inline]
+				fn deserialize_elements<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E, on_failure: ElementFailure<T>)
+							-> Result<Option<Self>, SyntaxError> {
+					deserialize_array(value, error_context, on_failure, $insert)
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_sets {
+	($($set:ident<T $(, $hasher:ident)?> where $($bound:ident),*);*) => {
+		$(
+			impl<'s, T, $($hasher,)? E> Deserialize<'s, E> for $set<T $(, $hasher)?>
+					where T: Deserialize<'s, E> $(+ $bound)*,
+						$($hasher: BuildHasher + Default + 's,)? E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					Self::deserialize_elements(value, error_context, ElementFailure::Drop)
+				}
+			}
+
+			impl<'s, T, $($hasher,)? E> DeserializeElements<'s, E> for $set<T $(, $hasher)?>
+					where T: Deserialize<'s, E> $(+ $bound)*,
+						$($hasher: BuildHasher + Default + 's,)? E: ErrorContext<'s> {
+				type Element = T;
+
+				#[inline]
+				fn deserialize_elements<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E, on_failure: ElementFailure<T>)
+							-> Result<Option<Self>, SyntaxError> {
+					deserialize_array(value, error_context, on_failure,
+						|set: &mut Self, value, error_context| {
+							if !set.insert(value) && error_context.duplicates() == Duplicates::Report {
+								error_context.report_duplicate_element();
+							}
+						})
+				}
+			}
+		)*
+	}
+}
+
+/// Deserializes every element of an array with its key pushed, passing the
+/// ones that succeed to `insert` while the key is still pushed.
+#[inline]
+fn deserialize_array<'d, 's, C, T, E, F>(value: ValueDeserializer<'d, 's>,
+		error_context: &mut E, on_failure: ElementFailure<T>, insert: F)
+			-> Result<Option<C>, SyntaxError>
+				where C: Default, T: Deserialize<'s, E>, E: ErrorContext<'s>,
+					F: FnMut(&mut C, T, &mut E) {
+	deserialize_array_with(value, error_context, on_failure, T::deserialize, insert)
+}
+
+/// Like [`deserialize_array`], deserializing each element with `element`.
+#[inline]
+fn deserialize_array_with<'d, 's, C, T, E, D, F>(value: ValueDeserializer<'d, 's>,
+		error_context: &mut E, on_failure: ElementFailure<T>, mut element: D,
+		mut insert: F) -> Result<Option<C>, SyntaxError>
+			where C: Default, E: ErrorContext<'s>,
+				D: FnMut(ValueDeserializer<'_, 's>, &mut E) -> Result<Option<T>, SyntaxError>,
+				F: FnMut(&mut C, T, &mut E) {
+	deserializer_match! {in value, error_context;
+		Array(mut array) => {
+			let mut result = C::default();
+			let mut failed = false;
+			let mut index = 0;
+			while let Some(value) = array.next_entry()? {
+				error_context.push_key(KeyKind::Array(index));
+				match (element(value, error_context)?, on_failure) {
+					(Some(value), _) => insert(&mut result, value, error_context),
+					(None, ElementFailure::Drop) => (),
+					(None, ElementFailure::Fail) => failed = true,
+					(None, ElementFailure::Substitute(substitute)) =>
+						insert(&mut result, substitute(), error_context)
+				}
+				error_context.pop_key();
+				index += 1;
+			}
+			Ok((!failed).then_some(result))
+		}
+	}
+}
+
+/// Deserializes every value of an object with its key pushed, passing the
+/// ones that succeed to `insert` alongside their name. `insert` returns
+/// whether the key was new, duplicates being reported as the error context's
+/// [`Duplicates`] policy asks.
+#[inline]
+pub(crate) fn deserialize_object<'d, 's, C, K, V, E, F>(value: ValueDeserializer<'d, 's>,
+		error_context: &mut E, on_failure: ElementFailure<V>, insert: F)
+			-> Result<Option<C>, SyntaxError>
+				where C: Default, K: DeserializeKey<'s, E>, V: Deserialize<'s, E>,
+					E: ErrorContext<'s>, F: FnMut(&mut C, K, V) -> bool {
+	deserialize_object_with(value, error_context, on_failure, V::deserialize, insert)
+}
+
+/// Like [`deserialize_object`], deserializing each value with `element`. Keys
+/// that fail to deserialize are always left out.
+#[inline]
+fn deserialize_object_with<'d, 's, C, K, V, E, D, F>(value: ValueDeserializer<'d, 's>,
+		error_context: &mut E, on_failure: ElementFailure<V>, mut element: D,
+		mut insert: F) -> Result<Option<C>, SyntaxError>
+			where C: Default, K: DeserializeKey<'s, E>, E: ErrorContext<'s>,
+				D: FnMut(ValueDeserializer<'_, 's>, &mut E) -> Result<Option<V>, SyntaxError>,
+				F: FnMut(&mut C, K, V) -> bool {
+	deserializer_match! {in value, error_context;
+		Object(mut object) => {
+			let mut result = C::default();
+			let mut failed = false;
+			while let Some(entry) = object.next_entry()? {
+				let (name, value) = entry.accept()?;
+				error_context.push_key(KeyKind::Object(name.clone()));
+				if let Some(key) = K::deserialize_key(name, error_context) {
+					let inserted = match (element(value, error_context)?, on_failure) {
+						(Some(value), _) => insert(&mut result, key, value),
+						(None, ElementFailure::Drop) => true,
+						(None, ElementFailure::Fail) => {failed = true; true},
+						(None, ElementFailure::Substitute(substitute)) =>
+							insert(&mut result, key, substitute())
+					};
+					if !inserted && error_context.duplicates() == Duplicates::Report {
+						error_context.report_duplicate_key();
+					}
+				}
+				error_context.pop_key();
+			}
+			Ok((!failed).then_some(result))
+		}
+	}
+}
+
+impl_sequences! {
+	VecDeque<T> where => |deque: &mut Self, value, _: &mut E| deque.push_back(value);
+	LinkedList<T> where => |list: &mut Self, value, _: &mut E| list.push_back(value);
+	BinaryHeap<T> where Ord => |heap: &mut Self, value, _: &mut E| heap.push(value)
 }
 
 impl<'s, T, E> Deserialize<'s, E> for Vec<T>
@@ -174,43 +1039,370 @@ impl<'s, T, E> Deserialize<'s, E> for Vec<T>
 	#[inline]
 	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
 			-> Result<Option<Self>, SyntaxError> {
+		Self::deserialize_elements(value, error_context, ElementFailure::Drop)
+	}
+
+	/// Deserializes elements over the existing ones while there are any, so
+	/// their allocations are reused, before pushing the rest.
+	#[inline]
+	fn deserialize_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+			error_context: &mut E) -> Result<bool, SyntaxError> {
+		self.deserialize_elements_in_place(value, error_context, ElementFailure::Drop)
+	}
+}
+
+impl<'s, T, E> DeserializeElements<'s, E> for Vec<T>
+		where T: Deserialize<'s, E>, E: ErrorContext<'s> {
+	type Element = T;
+
+	#[inline]
+	fn deserialize_elements<'d>(value: ValueDeserializer<'d, 's>,
+			error_context: &mut E, on_failure: ElementFailure<T>)
+				-> Result<Option<Self>, SyntaxError> {
+		deserialize_array(value, error_context, on_failure,
+			|vec: &mut Self, value, _: &mut E| vec.push(value))
+	}
+
+	#[inline]
+	fn deserialize_elements_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+			error_context: &mut E, on_failure: ElementFailure<T>)
+				-> Result<bool, SyntaxError> {
 		deserializer_match! {in value, error_context;
 			Array(mut array) => {
-				let mut result = Vec::new();
 				let mut index = 0;
+				let mut length = 0;
+				let mut failed = false;
 				while let Some(value) = array.next_entry()? {
 					error_context.push_key(KeyKind::Array(index));
-					if let Some(value) = T::deserialize(value, error_context)? {
-						result.push(value);
-					}
+					let success = match self.get_mut(length) {
+						Some(element) => element.deserialize_in_place(value, error_context)?,
+						None => match T::deserialize(value, error_context)? {
+							Some(element) => {self.push(element); true},
+							None => false
+						}
+					};
 					error_context.pop_key();
+					match (success, on_failure) {
+						(true, _) => length += 1,
+						// The failed element's slot is overwritten by the next one.
+						(false, ElementFailure::Drop) => (),
+						(false, ElementFailure::Fail) => failed = true,
+						(false, ElementFailure::Substitute(substitute)) => {
+							match self.get_mut(length) {
+								Some(element) => *element = substitute(),
+								None => self.push(substitute())
+							}
+							length += 1;
+						}
+					}
 					index += 1;
 				}
-				Ok(Some(result))
+				self.truncate(length);
+				Ok((!failed).then_some(()))
 			}
 		}
+		.map(|result: Option<()>| result.is_some())
 	}
 }
 
-impl<'s, T, U, E> Deserialize<'s, E> for HashMap<T, U>
-		where T: From<Cow<'s, str>> + Hash + Eq + 's, U: Deserialize<'s, E>,
-			E: ErrorContext<'s> {
+impl_sets! {
+	HashSet<T, S> where Hash, Eq;
+	BTreeSet<T> where Ord
+}
+
+impl<'s, T, E, const N: usize> Deserialize<'s, E> for [T; N]
+		where T: Deserialize<'s, E>, E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		deserializer_match! {in value, error_context;
+			Array(mut array) => {
+				let mut result = Vec::with_capacity(N);
+				let mut complete = true;
+				for index in 0..N {
+					match array.next_entry()? {
+						Some(value) => {
+							error_context.push_key(KeyKind::Array(index));
+							match T::deserialize(value, error_context)? {
+								Some(value) => result.push(value),
+								None => complete = false
+							}
+							error_context.pop_key();
+						},
+						None => {
+							error_context.report_array_too_short(N, index);
+							return Ok(None)
+						}
+					}
+				}
+
+				if !finish_array(array, N, error_context)? || !complete {
+					return Ok(None)
+				}
+				Ok(result.try_into().ok())
+			}
+		}
+	}
+}
+
+impl_tuples! {[]
+	0 T0 t0 1 T1 t1 2 T2 t2 3 T3 t3 4 T4 t4 5 T5 t5 6 T6 t6 7 T7 t7
+	8 T8 t8 9 T9 t9 10 T10 t10 11 T11 t11 12 T12 t12 13 T13 t13 14 T14 t14
+	15 T15 t15
+}
+
+impl<'s, T, U, S, E> Deserialize<'s, E> for HashMap<T, U, S>
+		where T: DeserializeKey<'s, E> + Hash + Eq, U: Deserialize<'s, E>,
+			S: BuildHasher + Default + 's, E: ErrorContext<'s> {
 	#[inline]
 	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
 			-> Result<Option<Self>, SyntaxError> {
+		Self::deserialize_elements(value, error_context, ElementFailure::Drop)
+	}
+
+	/// Updates the existing table, deserializing values over those previously
+	/// under the same key, so that the table, the keys and the values keep
+	/// their allocations.
+	#[inline]
+	fn deserialize_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+			error_context: &mut E) -> Result<bool, SyntaxError> {
+		self.deserialize_elements_in_place(value, error_context, ElementFailure::Drop)
+	}
+}
+
+impl<'s, T, U, S, E> DeserializeElements<'s, E> for HashMap<T, U, S>
+		where T: DeserializeKey<'s, E> + Hash + Eq, U: Deserialize<'s, E>,
+			S: BuildHasher + Default + 's, E: ErrorContext<'s> {
+	type Element = U;
+
+	#[inline]
+	fn deserialize_elements<'d>(value: ValueDeserializer<'d, 's>,
+			error_context: &mut E, on_failure: ElementFailure<U>)
+				-> Result<Option<Self>, SyntaxError> {
+		deserialize_object(value, error_context, on_failure,
+			|map: &mut Self, key, value| map.insert(key, value).is_none())
+	}
+
+	#[inline]
+	fn deserialize_elements_in_place<'d>(&mut self, value: ValueDeserializer<'d, 's>,
+			error_context: &mut E, on_failure: ElementFailure<U>)
+				-> Result<bool, SyntaxError> {
 		deserializer_match! {in value, error_context;
 			Object(mut object) => {
-				let mut result = HashMap::new();
+				// Existing entries are updated in place and kept if their key is
+				// seen, new ones are added once the others have been removed.
+				let mut seen = HashSet::with_hasher(S::default());
+				let mut added = HashMap::with_hasher(S::default());
+				let mut failed = false;
 				while let Some(entry) = object.next_entry()? {
 					let (name, value) = entry.accept()?;
 					error_context.push_key(KeyKind::Object(name.clone()));
-					if let Some(value) = U::deserialize(value, error_context)? {
-						result.insert(name.into(), value);
+					if let Some(key) = T::deserialize_key(name, error_context) {
+						let duplicate = match self.get_mut(&key) {
+							Some(existing) => {
+								let success = existing.deserialize_in_place(value, error_context)?;
+								let kept = match (success, on_failure) {
+									(true, _) => true,
+									(false, ElementFailure::Drop) => false,
+									(false, ElementFailure::Fail) => {failed = true; false},
+									(false, ElementFailure::Substitute(substitute)) => {
+										*existing = substitute();
+										true
+									}
+								};
+								match kept {
+									true => !seen.insert(key),
+									false => {seen.remove(&key); false}
+								}
+							},
+							None => {
+								let value = U::deserialize(value, error_context)?;
+								let value = match (value, on_failure) {
+									(Some(value), _) => Some(value),
+									(None, ElementFailure::Drop) => None,
+									(None, ElementFailure::Fail) => {failed = true; None},
+									(None, ElementFailure::Substitute(substitute)) => Some(substitute())
+								};
+								value.is_some_and(|value| added.insert(key, value).is_some())
+							}
+						};
+						if duplicate && error_context.duplicates() == Duplicates::Report {
+							error_context.report_duplicate_key();
+						}
 					}
 					error_context.pop_key();
 				}
-				Ok(Some(result))
+				self.retain(|key, _| seen.contains(key));
+				self.extend(added);
+				Ok((!failed).then_some(()))
+			}
+		}
+		.map(|result: Option<()>| result.is_some())
+	}
+}
+
+impl<'s, T, U, E> Deserialize<'s, E> for BTreeMap<T, U>
+		where T: DeserializeKey<'s, E> + Ord, U: Deserialize<'s, E>,
+			E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		Self::deserialize_elements(value, error_context, ElementFailure::Drop)
+	}
+}
+
+impl<'s, T, U, E> DeserializeElements<'s, E> for BTreeMap<T, U>
+		where T: DeserializeKey<'s, E> + Ord, U: Deserialize<'s, E>,
+			E: ErrorContext<'s> {
+	type Element = U;
+
+	#[inline]
+	fn deserialize_elements<'d>(value: ValueDeserializer<'d, 's>,
+			error_context: &mut E, on_failure: ElementFailure<U>)
+				-> Result<Option<Self>, SyntaxError> {
+		deserialize_object(value, error_context, on_failure,
+			|map: &mut Self, key, value| map.insert(key, value).is_none())
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for Cow<'s, str>
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, _: &mut E) -> Option<Self> {
+		Some(key)
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for String
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, _: &mut E) -> Option<Self> {
+		Some(key.into_owned())
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for Box<str>
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, _: &mut E) -> Option<Self> {
+		Some(key.into())
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for Rc<str>
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, _: &mut E) -> Option<Self> {
+		Some(Rc::from(&*key))
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for Arc<str>
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, _: &mut E) -> Option<Self> {
+		Some(Arc::from(&*key))
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for &'s str
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, error_context: &mut E) -> Option<Self> {
+		match key {
+			Cow::Borrowed(key) => Some(key),
+			Cow::Owned(_) => {
+				error_context.report_string_expected_borrowed();
+				None
+			}
+		}
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for bool
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, error_context: &mut E) -> Option<Self> {
+		match &*key {
+			"true" => Some(true),
+			"false" => Some(false),
+			_ => {
+				error_context.report_key_unparsable(JSONType::Boolean.mention_by_noun());
+				None
+			}
+		}
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for char
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, error_context: &mut E) -> Option<Self> {
+		let mut chars = key.chars();
+		match (chars.next(), chars.next()) {
+			(Some(char), None) => Some(char),
+			_ => {
+				error_context.report_string_expected_char();
+				None
 			}
 		}
 	}
 }
+
+impl<'s, T, E> DeserializeSeed<'s, E> for PhantomData<T>
+		where T: Deserialize<'s, E>, E: ErrorContext<'s> {
+	type Value = T;
+
+	#[inline]
+	fn deserialize<'d>(self, value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self::Value>, SyntaxError> {
+		T::deserialize(value, error_context)
+	}
+}
+
+/// Deserializes an array into a [`Vec`], with a clone of the seed for each
+/// element.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct VecSeed<S>(pub S);
+
+impl<'s, S, E> DeserializeSeed<'s, E> for VecSeed<S>
+		where S: DeserializeSeed<'s, E> + Clone, E: ErrorContext<'s> {
+	type Value = Vec<S::Value>;
+
+	#[inline]
+	fn deserialize<'d>(self, value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self::Value>, SyntaxError> {
+		deserialize_array_with(value, error_context, ElementFailure::Drop,
+			|value, error_context| self.0.clone().deserialize(value, error_context),
+			|vec: &mut Self::Value, value, _: &mut E| vec.push(value))
+	}
+}
+
+/// Deserializes an object into a [`HashMap`] with keys of type `K`, with a
+/// clone of the seed for each value.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MapSeed<K, S> {
+	seed: S,
+	key: PhantomData<K>
+}
+
+impl<K, S> MapSeed<K, S> {
+	#[inline]
+	pub fn new(seed: S) -> Self {
+		Self {seed, key: PhantomData}
+	}
+}
+
+impl<'s, K, S, E> DeserializeSeed<'s, E> for MapSeed<K, S>
+		where K: DeserializeKey<'s, E> + Hash + Eq, S: DeserializeSeed<'s, E> + Clone,
+			E: ErrorContext<'s> {
+	type Value = HashMap<K, S::Value>;
+
+	#[inline]
+	fn deserialize<'d>(self, value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self::Value>, SyntaxError> {
+		deserialize_object_with(value, error_context, ElementFailure::Drop,
+			|value, error_context| self.seed.clone().deserialize(value, error_context),
+			|map: &mut Self::Value, key, value| map.insert(key, value).is_none())
+	}
+}
@@ -1,4 +1,16 @@
-//#[cfg(test)]
-//mod tests;
+// Lets code generated by the derives, which names `::qj`, compile in this crate's tests.
+extern crate self as qj;
+
+#[cfg(test)]
+mod tests;
 pub mod deserialize;
+pub mod map;
+pub mod pointer;
+pub mod serialize;
+pub mod timestamp;
 mod util;
+pub mod value;
+
+pub use deserialize::{from_str, from_str_at};
+pub use serialize::to_string;
+pub use value::Value;
@@ -0,0 +1,361 @@
+//! A map keeping its entries in the order they were inserted.
+
+use super::{
+	deserialize::{
+		error::{ErrorContext, SyntaxError},
+		r#trait::{deserialize_object, Deserialize, DeserializeElements, DeserializeKey, ElementFailure},
+		ValueDeserializer
+	},
+	serialize::{
+		format::Formatter as SerializeFormatter, r#trait::{Serialize, SerializeKey},
+		Error as SerializeError, ValueSerializer
+	}
+};
+use indexmap::{map, IndexMap};
+use std::{
+	borrow::Borrow,
+	fmt::{Debug, Formatter, Result as FMTResult},
+	hash::Hash,
+	ops::{Index, IndexMut}
+};
+
+/// A map iterating over its entries in the order their keys were first
+/// inserted, such as the order of an object's entries in a document, while
+/// still looking keys up in constant time.
+#[derive(Clone)]
+pub struct Map<K, V>(IndexMap<K, V>);
+
+impl<K, V> Map<K, V> {
+	#[inline]
+	pub fn new() -> Self {
+		Self(IndexMap::new())
+	}
+
+	#[inline]
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self(IndexMap::with_capacity(capacity))
+	}
+
+	#[inline]
+	pub fn len(&self) -> usize {
+		self.0.len()
+	}
+
+	#[inline]
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	#[inline]
+	pub fn clear(&mut self) {
+		self.0.clear();
+	}
+
+	/// The entry at a position in insertion order.
+	#[inline]
+	pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+		self.0.get_index(index)
+	}
+
+	#[inline]
+	pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+		self.0.get_index_mut(index)
+	}
+
+	#[inline]
+	pub fn iter(&self) -> Iter<'_, K, V> {
+		Iter(self.0.iter())
+	}
+
+	#[inline]
+	pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+		IterMut(self.0.iter_mut())
+	}
+
+	#[inline]
+	pub fn keys(&self) -> impl DoubleEndedIterator<Item = &K> + ExactSizeIterator {
+		self.0.keys()
+	}
+
+	#[inline]
+	pub fn values(&self) -> impl DoubleEndedIterator<Item = &V> + ExactSizeIterator {
+		self.0.values()
+	}
+
+	#[inline]
+	pub fn values_mut(&mut self) -> impl DoubleEndedIterator<Item = &mut V> + ExactSizeIterator {
+		self.0.values_mut()
+	}
+}
+
+impl<K, V> Map<K, V>
+		where K: Hash + Eq {
+	/// The position of a key in insertion order.
+	#[inline]
+	pub fn index_of<Q>(&self, key: &Q) -> Option<usize>
+			where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+		self.0.get_index_of(key)
+	}
+
+	#[inline]
+	pub fn contains_key<Q>(&self, key: &Q) -> bool
+			where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+		self.0.contains_key(key)
+	}
+
+	#[inline]
+	pub fn get<Q>(&self, key: &Q) -> Option<&V>
+			where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+		self.0.get(key)
+	}
+
+	#[inline]
+	pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+			where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+		self.0.get_mut(key)
+	}
+
+	/// Removes an entry, shifting those after it to keep their order, which
+	/// takes time linear in their number.
+	#[inline]
+	pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+			where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+		self.0.shift_remove(key)
+	}
+
+	/// Inserts an entry, returning the value previously under the key. An
+	/// existing key keeps its position.
+	#[inline]
+	pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+		self.0.insert(key, value)
+	}
+
+	/// The value under a key, inserting the one returned by `value` last if
+	/// there is none yet.
+	#[inline]
+	pub fn get_or_insert_with<F>(&mut self, key: K, value: F) -> &mut V
+			where F: FnOnce() -> V {
+		self.0.entry(key).or_insert_with(value)
+	}
+}
+
+impl<K, V> Default for Map<K, V> {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<K, V> Debug for Map<K, V>
+		where K: Debug, V: Debug {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		f.debug_map().entries(self.iter()).finish()
+	}
+}
+
+/// Maps are equal if they have the same entries in the same order.
+impl<K, V> PartialEq for Map<K, V>
+		where K: PartialEq, V: PartialEq {
+	#[inline]
+	fn eq(&self, other: &Self) -> bool {
+		self.len() == other.len() && self.iter().eq(other.iter())
+	}
+}
+
+impl<K, V> Eq for Map<K, V>
+		where K: Eq, V: Eq {}
+
+/// # Panics
+///
+/// Panics if the key is not in the map.
+impl<K, V, Q> Index<&Q> for Map<K, V>
+		where K: Borrow<Q> + Hash + Eq, Q: Hash + Eq + ?Sized {
+	type Output = V;
+
+	#[inline]
+	fn index(&self, key: &Q) -> &V {
+		self.get(key).expect("key is not in the map")
+	}
+}
+
+/// # Panics
+///
+/// Panics if the key is not in the map.
+impl<K, V, Q> IndexMut<&Q> for Map<K, V>
+		where K: Borrow<Q> + Hash + Eq, Q: Hash + Eq + ?Sized {
+	#[inline]
+	fn index_mut(&mut self, key: &Q) -> &mut V {
+		self.get_mut(key).expect("key is not in the map")
+	}
+}
+
+impl<K, V> FromIterator<(K, V)> for Map<K, V>
+		where K: Hash + Eq {
+	#[inline]
+	fn from_iter<I>(iter: I) -> Self
+			where I: IntoIterator<Item = (K, V)> {
+		let mut map = Self::new();
+		map.extend(iter);
+		map
+	}
+}
+
+impl<K, V> Extend<(K, V)> for Map<K, V>
+		where K: Hash + Eq {
+	#[inline]
+	fn extend<I>(&mut self, iter: I)
+			where I: IntoIterator<Item = (K, V)> {
+		for (key, value) in iter {
+			self.insert(key, value);
+		}
+	}
+}
+
+/// Iterates over the entries of a [`Map`] in insertion order.
+#[derive(Clone, Debug)]
+pub struct Iter<'a, K, V>(map::Iter<'a, K, V>);
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+	type Item = (&'a K, &'a V);
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next()
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.0.size_hint()
+	}
+}
+
+impl<K, V> DoubleEndedIterator for Iter<'_, K, V> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.0.next_back()
+	}
+}
+
+impl<K, V> ExactSizeIterator for Iter<'_, K, V> {}
+
+/// Iterates over the entries of a [`Map`] in insertion order, with mutable
+/// values.
+#[derive(Debug)]
+pub struct IterMut<'a, K, V>(map::IterMut<'a, K, V>);
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+	type Item = (&'a K, &'a mut V);
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next()
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.0.size_hint()
+	}
+}
+
+impl<K, V> DoubleEndedIterator for IterMut<'_, K, V> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.0.next_back()
+	}
+}
+
+impl<K, V> ExactSizeIterator for IterMut<'_, K, V> {}
+
+/// Moves the entries out of a [`Map`] in insertion order.
+#[derive(Debug)]
+pub struct IntoIter<K, V>(map::IntoIter<K, V>);
+
+impl<K, V> Iterator for IntoIter<K, V> {
+	type Item = (K, V);
+
+	#[inline]
+	fn next(&mut self) -> Option<Self::Item> {
+		self.0.next()
+	}
+
+	#[inline]
+	fn size_hint(&self) -> (usize, Option<usize>) {
+		self.0.size_hint()
+	}
+}
+
+impl<K, V> DoubleEndedIterator for IntoIter<K, V> {
+	#[inline]
+	fn next_back(&mut self) -> Option<Self::Item> {
+		self.0.next_back()
+	}
+}
+
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+
+impl<K, V> IntoIterator for Map<K, V> {
+	type Item = (K, V);
+	type IntoIter = IntoIter<K, V>;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		IntoIter(self.0.into_iter())
+	}
+}
+
+impl<'a, K, V> IntoIterator for &'a Map<K, V> {
+	type Item = (&'a K, &'a V);
+	type IntoIter = Iter<'a, K, V>;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter()
+	}
+}
+
+impl<'a, K, V> IntoIterator for &'a mut Map<K, V> {
+	type Item = (&'a K, &'a mut V);
+	type IntoIter = IterMut<'a, K, V>;
+
+	#[inline]
+	fn into_iter(self) -> Self::IntoIter {
+		self.iter_mut()
+	}
+}
+
+impl<'s, K, V, E> Deserialize<'s, E> for Map<K, V>
+		where K: DeserializeKey<'s, E> + Hash + Eq, V: Deserialize<'s, E>,
+			E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		Self::deserialize_elements(value, error_context, ElementFailure::Drop)
+	}
+}
+
+impl<'s, K, V, E> DeserializeElements<'s, E> for Map<K, V>
+		where K: DeserializeKey<'s, E> + Hash + Eq, V: Deserialize<'s, E>,
+			E: ErrorContext<'s> {
+	type Element = V;
+
+	#[inline]
+	fn deserialize_elements<'d>(value: ValueDeserializer<'d, 's>,
+			error_context: &mut E, on_failure: ElementFailure<V>)
+				-> Result<Option<Self>, SyntaxError> {
+		deserialize_object(value, error_context, on_failure,
+			|map: &mut Self, key, value| map.insert(key, value).is_none())
+	}
+}
+
+impl<K, V> Serialize for Map<K, V>
+		where K: SerializeKey, V: Serialize {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), SerializeError>
+			where F: SerializeFormatter {
+		let mut object = value.object()?;
+		for (key, value) in self {
+			object.field(&key.serialize_key(), value)?;
+		}
+		object.end()
+	}
+}
@@ -0,0 +1,247 @@
+//! Locations within a document as specified by RFC 6901, JSON Pointer.
+
+use super::{deserialize::error::KeyKind, value::Value};
+use std::{
+	borrow::Cow,
+	error::Error,
+	fmt::{Display, Formatter, Result as FMTResult, Write},
+	mem::replace,
+	str::FromStr
+};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum PointerError {
+	/// A pointer other than the empty one does not start with `/`.
+	ExpectedSlash,
+	/// A `~` is not followed by `0` or `1`.
+	InvalidEscape,
+	/// The container a value would be inserted into does not exist.
+	NotFound,
+	/// A value would be inserted into an array at a position that is not a
+	/// number, `-`, or within its bounds.
+	InvalidIndex
+}
+
+impl Display for PointerError {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		f.write_str(match self {
+			Self::ExpectedSlash => "expected the pointer to start with '/'",
+			Self::InvalidEscape => "expected '0' or '1' after '~'",
+			Self::NotFound => "no object or array to insert into",
+			Self::InvalidIndex => "invalid array position"
+		})
+	}
+}
+
+impl Error for PointerError {}
+
+/// A sequence of reference tokens, each of which selects an entry of an
+/// object by its key or an element of an array by its position. The empty
+/// pointer refers to the whole document.
+#[derive(Clone, Debug, Default, Eq, Hash, PartialEq)]
+pub struct JsonPointer {
+	tokens: Vec<String>
+}
+
+/// Parses a token as an array position, which has no leading zeroes.
+pub(crate) fn array_index(token: &str) -> Option<usize> {
+	match token.as_bytes() {
+		[b'0'] => Some(0),
+		[b'1'..=b'9', rest @ ..] if rest.iter().all(u8::is_ascii_digit) => token.parse().ok(),
+		_ => None
+	}
+}
+
+fn walk<'v, 's>(tokens: &[String], value: &'v Value<'s>) -> Option<&'v Value<'s>> {
+	tokens.iter().try_fold(value, |value, token| match value {
+		Value::Object(object) => object.get(token.as_str()),
+		Value::Array(array) => array.get(array_index(token)?),
+		_ => None
+	})
+}
+
+fn walk_mut<'v, 's>(tokens: &[String], value: &'v mut Value<'s>)
+		-> Option<&'v mut Value<'s>> {
+	tokens.iter().try_fold(value, |value, token| match value {
+		Value::Object(object) => object.get_mut(token.as_str()),
+		Value::Array(array) => array.get_mut(array_index(token)?),
+		_ => None
+	})
+}
+
+impl JsonPointer {
+	/// The pointer to the whole document.
+	#[inline]
+	pub fn root() -> Self {
+		Self::default()
+	}
+
+	#[inline]
+	pub fn tokens(&self) -> &[String] {
+		&self.tokens
+	}
+
+	#[inline]
+	pub fn is_root(&self) -> bool {
+		self.tokens.is_empty()
+	}
+
+	/// Appends a token, unescaped.
+	#[inline]
+	pub fn push<T>(&mut self, token: T)
+			where T: Into<String> {
+		self.tokens.push(token.into());
+	}
+
+	#[inline]
+	pub fn pop(&mut self) -> Option<String> {
+		self.tokens.pop()
+	}
+
+	/// The tokens as keys, those that are array positions as
+	/// [`KeyKind::Array`] and the others as [`KeyKind::Object`]. Whether a
+	/// token such as `0` refers to an array or an object depends on the
+	/// document, so this is only a guess.
+	pub fn keys(&self) -> Vec<KeyKind<'_>> {
+		self.tokens.iter()
+			.map(|token| match array_index(token) {
+				Some(index) => KeyKind::Array(index),
+				None => KeyKind::Object(Cow::Borrowed(token))
+			})
+			.collect()
+	}
+
+	pub fn get<'v, 's>(&self, value: &'v Value<'s>) -> Option<&'v Value<'s>> {
+		walk(&self.tokens, value)
+	}
+
+	pub fn get_mut<'v, 's>(&self, value: &'v mut Value<'s>) -> Option<&'v mut Value<'s>> {
+		walk_mut(&self.tokens, value)
+	}
+
+	/// Inserts a value as the "add" operation of JSON Patch does: an object's
+	/// entry is inserted or replaced, returning the previous value, while
+	/// array elements are inserted before the given position, or at the end
+	/// for `-`. The container itself has to exist.
+	pub fn insert<'s>(&self, value: &mut Value<'s>, new: Value<'s>)
+			-> Result<Option<Value<'s>>, PointerError> {
+		let (last, parent) = match self.tokens.split_last() {
+			Some(split) => split,
+			None => return Ok(Some(replace(value, new)))
+		};
+
+		match walk_mut(parent, value) {
+			Some(Value::Object(object)) => Ok(object.insert(Cow::Owned(last.clone()), new)),
+			Some(Value::Array(array)) => {
+				let index = match last.as_str() {
+					"-" => array.len(),
+					last => array_index(last)
+						.filter(|index| *index <= array.len())
+						.ok_or(PointerError::InvalidIndex)?
+				};
+				array.insert(index, new);
+				Ok(None)
+			},
+			_ => Err(PointerError::NotFound)
+		}
+	}
+
+	/// Removes a value, shifting the elements after it if it is in an array.
+	/// Removing the whole document leaves `null` in its place.
+	pub fn remove<'s>(&self, value: &mut Value<'s>) -> Option<Value<'s>> {
+		let (last, parent) = match self.tokens.split_last() {
+			Some(split) => split,
+			None => return Some(value.take())
+		};
+
+		match walk_mut(parent, value)? {
+			Value::Object(object) => object.remove(last.as_str()),
+			Value::Array(array) => {
+				let index = array_index(last).filter(|index| *index < array.len())?;
+				Some(array.remove(index))
+			},
+			_ => None
+		}
+	}
+}
+
+impl FromStr for JsonPointer {
+	type Err = PointerError;
+
+	fn from_str(pointer: &str) -> Result<Self, Self::Err> {
+		if pointer.is_empty() {
+			return Ok(Self::root())
+		}
+
+		let tokens = pointer.strip_prefix('/').ok_or(PointerError::ExpectedSlash)?;
+		tokens.split('/')
+			.map(|token| {
+				let mut unescaped = String::with_capacity(token.len());
+				let mut chars = token.chars();
+				while let Some(char) = chars.next() {
+					unescaped.push(match char {
+						'~' => match chars.next() {
+							Some('0') => '~',
+							Some('1') => '/',
+							_ => return Err(PointerError::InvalidEscape)
+						},
+						char => char
+					});
+				}
+				Ok(unescaped)
+			})
+			.collect::<Result<_, _>>()
+			.map(|tokens| Self {tokens})
+	}
+}
+
+impl Display for JsonPointer {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		for token in &self.tokens {
+			f.write_char('/')?;
+			for char in token.chars() {
+				match char {
+					'~' => f.write_str("~0")?,
+					'/' => f.write_str("~1")?,
+					char => f.write_char(char)?
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+impl<'s> FromIterator<KeyKind<'s>> for JsonPointer {
+	fn from_iter<I>(keys: I) -> Self
+			where I: IntoIterator<Item = KeyKind<'s>> {
+		let tokens = keys.into_iter()
+			.map(|key| match key {
+				KeyKind::Object(name) => name.into_owned(),
+				KeyKind::Array(index) => index.to_string()
+			})
+			.collect();
+		Self {tokens}
+	}
+}
+
+impl<'s> From<Vec<KeyKind<'s>>> for JsonPointer {
+	#[inline]
+	fn from(keys: Vec<KeyKind<'s>>) -> Self {
+		keys.into_iter().collect()
+	}
+}
+
+impl<'s> From<&[KeyKind<'s>]> for JsonPointer {
+	#[inline]
+	fn from(keys: &[KeyKind<'s>]) -> Self {
+		keys.iter().cloned().collect()
+	}
+}
+
+/// Converts the tokens as [`JsonPointer::keys`] does.
+impl From<JsonPointer> for Vec<KeyKind<'static>> {
+	#[inline]
+	fn from(pointer: JsonPointer) -> Self {
+		pointer.keys().into_iter().map(KeyKind::into_static).collect()
+	}
+}
@@ -0,0 +1,284 @@
+//! Canonical JSON as specified by RFC 8785, the JSON Canonicalization Scheme.
+
+use super::{
+	format::{Escape, Formatter},
+	r#trait::Serialize, to_string_with, write_string, Error
+};
+use crate::deserialize::{Deserializer, ValueDeserializer};
+use std::{fmt::{self, Result as FMTResult, Write}, mem::take};
+
+/// Rewrites a JSON document canonically: without whitespace, with object keys
+/// sorted by their UTF-16 code units, numbers formatted as ECMAScript does and
+/// strings escaped minimally.
+#[inline]
+pub fn canonicalize(input: &str) -> Result<String, Error> {
+	canonicalize_with(input, Escape::Minimal)
+}
+
+/// Rewrites a JSON document as [`canonicalize`] does, but escaping strings as
+/// given. Only [`Escape::Minimal`] output conforms to RFC 8785.
+pub fn canonicalize_with(input: &str, escape: Escape) -> Result<String, Error> {
+	let mut deserializer = Deserializer::new(input);
+	let node = Node::read(ValueDeserializer::new(&mut deserializer)?)?;
+	deserializer.finish()?;
+
+	let mut output = String::new();
+	node.write(&mut output, escape)?;
+	Ok(output)
+}
+
+/// Serializes a value canonically, as [`canonicalize`] does.
+#[inline]
+pub fn to_string_canonical<T>(value: &T) -> Result<String, Error>
+		where T: Serialize + ?Sized {
+	to_string_canonical_with(value, Escape::Minimal)
+}
+
+/// Serializes a value as [`canonicalize_with`] does.
+#[inline]
+pub fn to_string_canonical_with<T>(value: &T, escape: Escape) -> Result<String, Error>
+		where T: Serialize + ?Sized {
+	to_string_with(value, Canonical::new(escape))
+}
+
+/// Writes canonical JSON as [`canonicalize_with`] does. Arrays and scalars
+/// outside of objects are written as they come; objects are held back until
+/// they end, as their entries have to be sorted first.
+#[derive(Clone, Debug, Default)]
+pub struct Canonical {
+	escape: Escape,
+	/// Objects being written, the innermost last.
+	objects: Vec<Object>
+}
+
+#[derive(Clone, Debug, Default)]
+struct Object {
+	/// Unescaped keys with their serialized values.
+	entries: Vec<(String, String)>,
+	/// The entry being written.
+	key: String,
+	value: String
+}
+
+impl Canonical {
+	#[inline]
+	pub fn new(escape: Escape) -> Self {
+		Self {escape, objects: Vec::new()}
+	}
+
+	/// Where output goes: the value of the innermost object's last entry, if
+	/// in an object.
+	#[inline]
+	fn sink<'a>(&'a mut self, writer: &'a mut dyn Write) -> &'a mut dyn Write {
+		match self.objects.last_mut() {
+			Some(object) => &mut object.value,
+			None => writer
+		}
+	}
+}
+
+impl Formatter for Canonical {
+	#[inline]
+	fn begin_object(&mut self, _writer: &mut dyn Write) -> FMTResult {
+		self.objects.push(Object::default());
+		Ok(())
+	}
+
+	#[inline]
+	fn begin_key(&mut self, _writer: &mut dyn Write, first: bool) -> FMTResult {
+		if let (Some(object), false) = (self.objects.last_mut(), first) {
+			let entry = (take(&mut object.key), take(&mut object.value));
+			object.entries.push(entry);
+		}
+		Ok(())
+	}
+
+	#[inline]
+	fn write_key(&mut self, _writer: &mut dyn Write, key: &str, _escape: Escape) -> FMTResult {
+		if let Some(object) = self.objects.last_mut() {
+			object.key.push_str(key);
+		}
+		Ok(())
+	}
+
+	#[inline]
+	fn begin_value(&mut self, _writer: &mut dyn Write) -> FMTResult {
+		Ok(())
+	}
+
+	fn end_object(&mut self, writer: &mut dyn Write, empty: bool) -> FMTResult {
+		let Some(mut object) = self.objects.pop() else {
+			panic!("object ended while not in one")
+		};
+		if !empty {
+			object.entries.push((object.key, object.value));
+		}
+		object.entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+
+		let escape = self.escape;
+		let sink = self.sink(writer);
+		sink.write_char('{')?;
+		for (index, (key, value)) in object.entries.iter().enumerate() {
+			if index != 0 {
+				sink.write_char(',')?;
+			}
+			write_string(sink, key, escape)?;
+			sink.write_char(':')?;
+			sink.write_str(value)?;
+		}
+		sink.write_char('}')
+	}
+
+	#[inline]
+	fn begin_array(&mut self, writer: &mut dyn Write) -> FMTResult {
+		self.sink(writer).write_char('[')
+	}
+
+	#[inline]
+	fn begin_element(&mut self, writer: &mut dyn Write, first: bool) -> FMTResult {
+		if first {Ok(())} else {self.sink(writer).write_char(',')}
+	}
+
+	#[inline]
+	fn end_array(&mut self, writer: &mut dyn Write, _empty: bool) -> FMTResult {
+		self.sink(writer).write_char(']')
+	}
+
+	/// Numbers are rewritten as ECMAScript formats them, which fails for
+	/// those out of the range of a double.
+	fn write_scalar(&mut self, writer: &mut dyn Write, scalar: &dyn Fn(&mut dyn Write) -> FMTResult)
+			-> FMTResult {
+		let mut text = String::new();
+		scalar(&mut text)?;
+		let sink = self.sink(writer);
+		match text.starts_with(|char: char| char == '-' || char.is_ascii_digit()) {
+			true => {
+				let number: f64 = text.parse().expect("number was serialized incorrectly");
+				if !number.is_finite() {
+					return Err(fmt::Error)
+				}
+				write_ecmascript_number(sink, number)
+			},
+			false => sink.write_str(&text)
+		}
+	}
+
+	#[inline]
+	fn escape(&self) -> Escape {
+		self.escape
+	}
+}
+
+enum Node {
+	/// Serialized text of a number, boolean or null.
+	Scalar(String),
+	String(String),
+	Array(Vec<Node>),
+	/// Entries sorted by their unescaped keys.
+	Object(Vec<(String, Node)>)
+}
+
+impl Node {
+	fn read(value: ValueDeserializer<'_, '_>) -> Result<Self, Error> {
+		Ok(match value {
+			ValueDeserializer::Object(mut object) => {
+				let mut entries = Vec::new();
+				while let Some(entry) = object.next_entry()? {
+					let (name, value) = entry.accept()?;
+					entries.push((name.into_owned(), Self::read(value)?));
+				}
+				entries.sort_by(|(a, _), (b, _)| a.encode_utf16().cmp(b.encode_utf16()));
+				Self::Object(entries)
+			},
+			ValueDeserializer::Array(mut array) => {
+				let mut elements = Vec::new();
+				while let Some(value) = array.next_entry()? {
+					elements.push(Self::read(value)?);
+				}
+				Self::Array(elements)
+			},
+			ValueDeserializer::String(string) => Self::String(string.accept()?.into_owned()),
+			ValueDeserializer::Number(number) => {
+				let number: f64 = number.accept()?.source.parse()
+					.expect("number was parsed incorrectly");
+				if !number.is_finite() {
+					return Err(Error::custom("number is out of the range of an IEEE 754 double"))
+				}
+
+				let mut text = String::new();
+				write_ecmascript_number(&mut text, number)?;
+				Self::Scalar(text)
+			},
+			ValueDeserializer::Boolean(boolean) => Self::Scalar(boolean.to_string()),
+			ValueDeserializer::Null => Self::Scalar("null".to_owned())
+		})
+	}
+
+	fn write(&self, writer: &mut dyn Write, escape: Escape) -> FMTResult {
+		match self {
+			Self::Scalar(text) => writer.write_str(text),
+			Self::String(string) => write_string(writer, string, escape),
+			Self::Array(elements) => {
+				writer.write_char('[')?;
+				for (index, element) in elements.iter().enumerate() {
+					if index != 0 {
+						writer.write_char(',')?;
+					}
+					element.write(writer, escape)?;
+				}
+				writer.write_char(']')
+			},
+			Self::Object(entries) => {
+				writer.write_char('{')?;
+				for (index, (key, value)) in entries.iter().enumerate() {
+					if index != 0 {
+						writer.write_char(',')?;
+					}
+					write_string(writer, key, escape)?;
+					writer.write_char(':')?;
+					value.write(writer, escape)?;
+				}
+				writer.write_char('}')
+			}
+		}
+	}
+}
+
+/// Formats a finite number as ECMAScript's `Number.prototype.toString` does.
+fn write_ecmascript_number(writer: &mut dyn Write, number: f64) -> FMTResult {
+	if number == 0. {
+		return writer.write_char('0')
+	}
+	if number < 0. {
+		writer.write_char('-')?;
+	}
+
+	// Shortest digits that read back as the same number, as `d.ddde±x`.
+	let scientific = format!("{:e}", number.abs());
+	let (mantissa, exponent) = scientific.split_once('e')
+		.expect("scientific notation has an exponent");
+	let digits = mantissa.replace('.', "");
+	let digits = digits.as_str();
+	let length = digits.len() as i32;
+	// The decimal point goes after the first `point` digits.
+	let point = exponent.parse::<i32>().expect("exponent is an integer") + 1;
+
+	match point {
+		point if length <= point && point <= 21 =>
+			write!(writer, "{}{:0<2$}", digits, "", (point - length) as usize),
+		point if 0 < point && point <= 21 => {
+			let (whole, fraction) = digits.split_at(point as usize);
+			write!(writer, "{}.{}", whole, fraction)
+		},
+		point if -6 < point && point <= 0 =>
+			write!(writer, "0.{:0<1$}{2}", "", -point as usize, digits),
+		point => {
+			let (first, rest) = digits.split_at(1);
+			let sign = if point > 0 {'+'} else {'-'};
+			match rest {
+				"" => write!(writer, "{}e{}{}", first, sign, (point - 1).abs()),
+				rest => write!(writer, "{}.{}e{}{}", first, rest, sign, (point - 1).abs())
+			}
+		}
+	}
+}
@@ -0,0 +1,345 @@
+use super::write_string;
+use std::fmt::{Result, Write};
+
+/// Decides the whitespace written around the structure of a document. Every
+/// method defaults to writing compact output.
+pub trait Formatter {
+	#[inline]
+	fn begin_object(&mut self, writer: &mut dyn Write) -> Result {
+		writer.write_char('{')
+	}
+
+	/// Called before each key, `first` being whether it is the first one.
+	#[inline]
+	fn begin_key(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		if first {Ok(())} else {writer.write_char(',')}
+	}
+
+	/// Writes the key of an entry, escaped as given.
+	#[inline]
+	fn write_key(&mut self, writer: &mut dyn Write, key: &str, escape: Escape) -> Result {
+		write_string(writer, key, escape)
+	}
+
+	/// Called between a key and its value.
+	#[inline]
+	fn begin_value(&mut self, writer: &mut dyn Write) -> Result {
+		writer.write_char(':')
+	}
+
+	#[inline]
+	fn end_object(&mut self, writer: &mut dyn Write, _empty: bool) -> Result {
+		writer.write_char('}')
+	}
+
+	#[inline]
+	fn begin_array(&mut self, writer: &mut dyn Write) -> Result {
+		writer.write_char('[')
+	}
+
+	/// Called before each element, `first` being whether it is the first one.
+	#[inline]
+	fn begin_element(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		if first {Ok(())} else {writer.write_char(',')}
+	}
+
+	#[inline]
+	fn end_array(&mut self, writer: &mut dyn Write, _empty: bool) -> Result {
+		writer.write_char(']')
+	}
+
+	/// Writes a string, number, boolean or null, given a function writing its
+	/// serialized text.
+	#[inline]
+	fn write_scalar(&mut self, writer: &mut dyn Write, scalar: &dyn Fn(&mut dyn Write) -> Result)
+			-> Result {
+		scalar(writer)
+	}
+
+	/// Decides which characters of strings and keys are escaped.
+	#[inline]
+	fn escape(&self) -> Escape {
+		Escape::Minimal
+	}
+}
+
+/// Which characters of strings are escaped, beyond the quote, the backslash
+/// and control characters that always are.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Escape {
+	#[default]
+	Minimal,
+	/// Also escapes `<`, `>`, `&`, U+2028 and U+2029, so that the output can
+	/// be embedded in a HTML `<script>` tag.
+	Html,
+	/// Also escapes every character outside of ASCII, as surrogate pairs
+	/// where needed.
+	Ascii
+}
+
+/// Writes no whitespace at all.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Compact;
+
+impl Formatter for Compact {}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Indent {
+	Spaces(usize),
+	Tabs
+}
+
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq)]
+pub enum Newline {
+	#[default]
+	Lf,
+	CrLf
+}
+
+impl Newline {
+	#[inline]
+	pub fn as_str(self) -> &'static str {
+		match self {
+			Self::Lf => "\n",
+			Self::CrLf => "\r\n"
+		}
+	}
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PrettyConfig {
+	/// Indentation of each level of nesting.
+	pub indent: Indent,
+	pub newline: Newline,
+	/// Whether a space is written between a key's colon and its value.
+	pub space_after_colon: bool,
+	/// Whether empty objects and arrays are written as `{}` and `[]` rather
+	/// than spread over two lines.
+	pub compact_empty: bool,
+	pub escape: Escape
+}
+
+impl Default for PrettyConfig {
+	fn default() -> Self {
+		Self {
+			indent: Indent::Tabs,
+			newline: Newline::Lf,
+			space_after_colon: true,
+			compact_empty: true,
+			escape: Escape::Minimal
+		}
+	}
+}
+
+/// Writes every key and element on its own line, indented by its depth.
+#[derive(Clone, Debug, Default)]
+pub struct Pretty {
+	config: PrettyConfig,
+	depth: usize
+}
+
+impl Pretty {
+	#[inline]
+	pub fn new(config: PrettyConfig) -> Self {
+		Self {config, depth: 0}
+	}
+
+	#[inline]
+	pub fn config(&self) -> &PrettyConfig {
+		&self.config
+	}
+
+	fn write_line(&self, writer: &mut dyn Write) -> Result {
+		writer.write_str(self.config.newline.as_str())?;
+		match self.config.indent {
+			Indent::Spaces(spaces) => write!(writer, "{:1$}", "", spaces * self.depth),
+			Indent::Tabs => (0..self.depth).try_for_each(|_| writer.write_char('\t'))
+		}
+	}
+
+	fn begin(&mut self, writer: &mut dyn Write, open: char) -> Result {
+		self.depth += 1;
+		writer.write_char(open)
+	}
+
+	fn next(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		if !first {
+			writer.write_char(',')?;
+		}
+		self.write_line(writer)
+	}
+
+	fn end(&mut self, writer: &mut dyn Write, empty: bool, close: char) -> Result {
+		self.depth -= 1;
+		if !(empty && self.config.compact_empty) {
+			self.write_line(writer)?;
+		}
+		writer.write_char(close)
+	}
+}
+
+impl Formatter for Pretty {
+	#[inline]
+	fn begin_object(&mut self, writer: &mut dyn Write) -> Result {
+		self.begin(writer, '{')
+	}
+
+	#[inline]
+	fn begin_key(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		self.next(writer, first)
+	}
+
+	#[inline]
+	fn begin_value(&mut self, writer: &mut dyn Write) -> Result {
+		writer.write_str(if self.config.space_after_colon {": "} else {":"})
+	}
+
+	#[inline]
+	fn end_object(&mut self, writer: &mut dyn Write, empty: bool) -> Result {
+		self.end(writer, empty, '}')
+	}
+
+	#[inline]
+	fn begin_array(&mut self, writer: &mut dyn Write) -> Result {
+		self.begin(writer, '[')
+	}
+
+	#[inline]
+	fn begin_element(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		self.next(writer, first)
+	}
+
+	#[inline]
+	fn end_array(&mut self, writer: &mut dyn Write, empty: bool) -> Result {
+		self.end(writer, empty, ']')
+	}
+
+	#[inline]
+	fn escape(&self) -> Escape {
+		self.config.escape
+	}
+}
+
+/// Writes whitespace as another formatter does, escaping strings as given.
+#[derive(Clone, Debug, Default)]
+pub struct Escaped<F>
+		where F: Formatter {
+	pub formatter: F,
+	pub escape: Escape
+}
+
+impl<F> Escaped<F>
+		where F: Formatter {
+	#[inline]
+	pub fn new(formatter: F, escape: Escape) -> Self {
+		Self {formatter, escape}
+	}
+}
+
+impl<F> Formatter for Escaped<F>
+		where F: Formatter {
+	#[inline]
+	fn begin_object(&mut self, writer: &mut dyn Write) -> Result {
+		self.formatter.begin_object(writer)
+	}
+
+	#[inline]
+	fn begin_key(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		self.formatter.begin_key(writer, first)
+	}
+
+	#[inline]
+	fn write_key(&mut self, writer: &mut dyn Write, key: &str, escape: Escape) -> Result {
+		self.formatter.write_key(writer, key, escape)
+	}
+
+	#[inline]
+	fn begin_value(&mut self, writer: &mut dyn Write) -> Result {
+		self.formatter.begin_value(writer)
+	}
+
+	#[inline]
+	fn end_object(&mut self, writer: &mut dyn Write, empty: bool) -> Result {
+		self.formatter.end_object(writer, empty)
+	}
+
+	#[inline]
+	fn begin_array(&mut self, writer: &mut dyn Write) -> Result {
+		self.formatter.begin_array(writer)
+	}
+
+	#[inline]
+	fn begin_element(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		self.formatter.begin_element(writer, first)
+	}
+
+	#[inline]
+	fn end_array(&mut self, writer: &mut dyn Write, empty: bool) -> Result {
+		self.formatter.end_array(writer, empty)
+	}
+
+	#[inline]
+	fn write_scalar(&mut self, writer: &mut dyn Write, scalar: &dyn Fn(&mut dyn Write) -> Result)
+			-> Result {
+		self.formatter.write_scalar(writer, scalar)
+	}
+
+	#[inline]
+	fn escape(&self) -> Escape {
+		self.escape
+	}
+}
+
+impl<F> Formatter for &mut F
+		where F: Formatter + ?Sized {
+	#[inline]
+	fn begin_object(&mut self, writer: &mut dyn Write) -> Result {
+		(**self).begin_object(writer)
+	}
+
+	#[inline]
+	fn begin_key(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		(**self).begin_key(writer, first)
+	}
+
+	#[inline]
+	fn write_key(&mut self, writer: &mut dyn Write, key: &str, escape: Escape) -> Result {
+		(**self).write_key(writer, key, escape)
+	}
+
+	#[inline]
+	fn begin_value(&mut self, writer: &mut dyn Write) -> Result {
+		(**self).begin_value(writer)
+	}
+
+	#[inline]
+	fn end_object(&mut self, writer: &mut dyn Write, empty: bool) -> Result {
+		(**self).end_object(writer, empty)
+	}
+
+	#[inline]
+	fn begin_array(&mut self, writer: &mut dyn Write) -> Result {
+		(**self).begin_array(writer)
+	}
+
+	#[inline]
+	fn begin_element(&mut self, writer: &mut dyn Write, first: bool) -> Result {
+		(**self).begin_element(writer, first)
+	}
+
+	#[inline]
+	fn end_array(&mut self, writer: &mut dyn Write, empty: bool) -> Result {
+		(**self).end_array(writer, empty)
+	}
+
+	#[inline]
+	fn write_scalar(&mut self, writer: &mut dyn Write, scalar: &dyn Fn(&mut dyn Write) -> Result)
+			-> Result {
+		(**self).write_scalar(writer, scalar)
+	}
+
+	#[inline]
+	fn escape(&self) -> Escape {
+		(**self).escape()
+	}
+}
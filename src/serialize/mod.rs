@@ -0,0 +1,445 @@
+pub mod canonical;
+pub mod format;
+pub mod smart;
+pub mod r#trait;
+pub mod writer;
+
+use self::{format::{Compact, Escape, Formatter, Pretty, PrettyConfig}, r#trait::Serialize};
+use crate::deserialize::{error::SyntaxError, Deserializer, ValueDeserializer};
+use std::{
+	fmt::{self, Display, Write},
+	io,
+	mem::{replace, ManuallyDrop},
+	ptr
+};
+
+#[derive(Debug)]
+pub enum Error {
+	/// The `fmt::Write` being written to failed.
+	Fmt(fmt::Error),
+	/// The `io::Write` being written to failed.
+	Io(io::Error),
+	/// The value cannot be represented as JSON.
+	Custom(String),
+	/// The text being reformatted is not valid JSON.
+	Syntax(SyntaxError)
+}
+
+impl Error {
+	#[inline]
+	pub fn custom<M>(message: M) -> Self
+			where M: Display {
+		Self::Custom(message.to_string())
+	}
+}
+
+impl From<fmt::Error> for Error {
+	#[inline]
+	fn from(error: fmt::Error) -> Self {
+		Self::Fmt(error)
+	}
+}
+
+impl From<io::Error> for Error {
+	#[inline]
+	fn from(error: io::Error) -> Self {
+		Self::Io(error)
+	}
+}
+
+impl From<SyntaxError> for Error {
+	#[inline]
+	fn from(error: SyntaxError) -> Self {
+		Self::Syntax(error)
+	}
+}
+
+impl Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Fmt(error) => error.fmt(f),
+			Self::Io(error) => error.fmt(f),
+			Self::Custom(message) => f.write_str(message),
+			Self::Syntax(error) => error.fmt(f)
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+
+pub fn to_string<T>(value: &T) -> Result<String, Error>
+		where T: Serialize + ?Sized {
+	to_string_with(value, Compact)
+}
+
+pub fn to_string_pretty<T>(value: &T, config: PrettyConfig) -> Result<String, Error>
+		where T: Serialize + ?Sized {
+	to_string_with(value, Pretty::new(config))
+}
+
+pub fn to_string_with<T, F>(value: &T, formatter: F) -> Result<String, Error>
+		where T: Serialize + ?Sized, F: Formatter {
+	let mut string = String::new();
+	to_writer_with(&mut string, value, formatter)?;
+	Ok(string)
+}
+
+pub fn to_writer<W, T>(writer: &mut W, value: &T) -> Result<(), Error>
+		where W: Write, T: Serialize + ?Sized {
+	to_writer_with(writer, value, Compact)
+}
+
+pub fn to_writer_with<W, T, F>(writer: &mut W, value: &T, formatter: F)
+		-> Result<(), Error>
+			where W: Write, T: Serialize + ?Sized, F: Formatter {
+	value.serialize(Serializer::with_formatter(writer, formatter).value())
+}
+
+pub fn to_io_writer<W, T>(writer: W, value: &T) -> Result<(), Error>
+		where W: io::Write, T: Serialize + ?Sized {
+	to_io_writer_with(writer, value, Compact)
+}
+
+pub fn to_io_writer_with<W, T, F>(writer: W, value: &T, formatter: F)
+		-> Result<(), Error>
+			where W: io::Write, T: Serialize + ?Sized, F: Formatter {
+	let mut writer = IoWriter {writer, error: None};
+	let result = to_writer_with(&mut writer, value, formatter);
+	match writer.error {
+		Some(error) => Err(Error::Io(error)),
+		None => result
+	}
+}
+
+/// Rewrites a JSON document with the given formatter, streaming it from the
+/// [`Deserializer`] rather than building a tree. Numbers are written as they
+/// appear in the input, strings are escaped anew.
+pub fn reformat<F>(input: &str, formatter: F) -> Result<String, Error>
+		where F: Formatter {
+	let mut output = String::new();
+	let mut deserializer = Deserializer::new(input);
+	let mut serializer = Serializer::with_formatter(&mut output, formatter);
+	transcode(ValueDeserializer::new(&mut deserializer)?, serializer.value())?;
+	deserializer.finish()?;
+	drop(serializer);
+	Ok(output)
+}
+
+/// Writes a value as it is read.
+pub fn transcode<F>(value: ValueDeserializer<'_, '_>, serializer: ValueSerializer<'_, '_, F>)
+		-> Result<(), Error>
+			where F: Formatter {
+	match value {
+		ValueDeserializer::Object(mut object) => {
+			let mut serializer = serializer.object()?;
+			while let Some(entry) = object.next_entry()? {
+				let (name, value) = entry.accept()?;
+				transcode(value, serializer.entry(&name)?)?;
+			}
+			serializer.end()
+		},
+		ValueDeserializer::Array(mut array) => {
+			let mut serializer = serializer.array()?;
+			while let Some(value) = array.next_entry()? {
+				transcode(value, serializer.element()?)?;
+			}
+			serializer.end()
+		},
+		ValueDeserializer::String(string) => serializer.string(&string.accept()?),
+		ValueDeserializer::Number(number) => serializer.raw(&number.accept()?.source),
+		ValueDeserializer::Boolean(boolean) => serializer.boolean(boolean),
+		ValueDeserializer::Null => serializer.null()
+	}
+}
+
+/// Adapts an `io::Write` to `fmt::Write`, keeping the error `fmt::Error`
+/// cannot carry.
+struct IoWriter<W>
+		where W: io::Write {
+	writer: W,
+	error: Option<io::Error>
+}
+
+impl<W> Write for IoWriter<W>
+		where W: io::Write {
+	#[inline]
+	fn write_str(&mut self, string: &str) -> fmt::Result {
+		self.writer.write_all(string.as_bytes()).map_err(|error| {
+			self.error = Some(error);
+			fmt::Error
+		})
+	}
+}
+
+/// The error for a float JSON cannot represent.
+#[inline]
+fn non_finite<N>(number: N) -> Error
+		where N: Display {
+	Error::custom(format!("{} cannot be represented as JSON", number))
+}
+
+pub(crate) fn write_string(writer: &mut dyn Write, string: &str, escape: Escape)
+		-> fmt::Result {
+	writer.write_char('"')?;
+
+	let mut start = 0;
+	for (index, char) in string.char_indices() {
+		let short = match char {
+			'"' => Some("\\\""),
+			'\\' => Some("\\\\"),
+			'\n' => Some("\\n"),
+			'\r' => Some("\\r"),
+			'\t' => Some("\\t"),
+			'\u{8}' => Some("\\b"),
+			'\u{C}' => Some("\\f"),
+			'\0'..='\u{1F}' => None,
+			'<' | '>' | '&' | '\u{2028}' | '\u{2029}' if escape == Escape::Html => None,
+			'\u{80}'.. if escape == Escape::Ascii => None,
+			_ => continue
+		};
+
+		writer.write_str(&string[start..index])?;
+		match short {
+			Some(short) => writer.write_str(short)?,
+			None => char.encode_utf16(&mut [0; 2]).iter()
+				.try_for_each(|unit| write!(writer, "\\u{:04x}", unit))?
+		}
+		start = index + char.len_utf8();
+	}
+
+	writer.write_str(&string[start..])?;
+	writer.write_char('"')
+}
+
+pub struct Serializer<'w, F = Compact>
+		where F: Formatter {
+	writer: &'w mut dyn Write,
+	formatter: F
+}
+
+impl<'w> Serializer<'w> {
+	#[inline]
+	pub fn new(writer: &'w mut dyn Write) -> Self {
+		Self::with_formatter(writer, Compact)
+	}
+}
+
+impl<'w, F> Serializer<'w, F>
+		where F: Formatter {
+	#[inline]
+	pub fn with_formatter(writer: &'w mut dyn Write, formatter: F) -> Self {
+		Self {writer, formatter}
+	}
+
+	#[inline]
+	pub fn value(&mut self) -> ValueSerializer<'_, 'w, F> {
+		ValueSerializer(self)
+	}
+}
+
+/// Serializes a single value. If dropped without one having been written,
+/// `null` is written in its place.
+pub struct ValueSerializer<'a, 'w, F = Compact>(&'a mut Serializer<'w, F>)
+	where 'w: 'a, F: Formatter;
+
+impl<'a, 'w, F> ValueSerializer<'a, 'w, F>
+		where 'w: 'a, F: Formatter {
+	#[inline]
+	fn into_serializer(self) -> &'a mut Serializer<'w, F> {
+		let this = ManuallyDrop::new(self);
+		unsafe {ptr::read(&this.0)}
+	}
+
+	#[inline]
+	fn write<W>(self, write: W) -> Result<(), Error>
+			where W: Fn(&mut dyn Write) -> fmt::Result {
+		let Serializer {writer, formatter} = self.into_serializer();
+		Ok(formatter.write_scalar(*writer, &write)?)
+	}
+
+	/// Consumes the serializer without writing anything, for a value that
+	/// cannot be written.
+	#[inline]
+	fn fail(self, error: Error) -> Error {
+		self.into_serializer();
+		error
+	}
+
+	#[inline]
+	pub fn null(self) -> Result<(), Error> {
+		self.write(|writer| writer.write_str("null"))
+	}
+
+	#[inline]
+	pub fn boolean(self, boolean: bool) -> Result<(), Error> {
+		self.write(|writer| writer.write_str(if boolean {"true"} else {"false"}))
+	}
+
+	#[inline]
+	pub fn string(self, string: &str) -> Result<(), Error> {
+		let escape = self.0.formatter.escape();
+		self.write(|writer| write_string(writer, string, escape))
+	}
+
+	#[inline]
+	pub fn u64(self, number: u64) -> Result<(), Error> {
+		self.write(|writer| write!(writer, "{}", number))
+	}
+
+	#[inline]
+	pub fn i64(self, number: i64) -> Result<(), Error> {
+		self.write(|writer| write!(writer, "{}", number))
+	}
+
+	#[inline]
+	pub fn u128(self, number: u128) -> Result<(), Error> {
+		self.write(|writer| write!(writer, "{}", number))
+	}
+
+	#[inline]
+	pub fn i128(self, number: i128) -> Result<(), Error> {
+		self.write(|writer| write!(writer, "{}", number))
+	}
+
+	/// Writes the shortest representation reading back as the same float.
+	/// JSON has no representation for NaN or the infinities, which are
+	/// rejected with an error rather than silently written as `null`.
+	#[inline]
+	pub fn f32(self, number: f32) -> Result<(), Error> {
+		match number.is_finite() {
+			true => self.write(|writer| write!(writer, "{:?}", number)),
+			false => Err(self.fail(non_finite(number)))
+		}
+	}
+
+	/// Writes the shortest representation reading back as the same float.
+	/// JSON has no representation for NaN or the infinities, which are
+	/// rejected with an error rather than silently written as `null`.
+	#[inline]
+	pub fn f64(self, number: f64) -> Result<(), Error> {
+		match number.is_finite() {
+			true => self.write(|writer| write!(writer, "{:?}", number)),
+			false => Err(self.fail(non_finite(number)))
+		}
+	}
+
+	/// Writes already serialized JSON as is.
+	#[inline]
+	pub(crate) fn raw(self, json: &str) -> Result<(), Error> {
+		self.write(|writer| writer.write_str(json))
+	}
+
+	#[inline]
+	pub fn object(self) -> Result<ObjectSerializer<'a, 'w, F>, Error> {
+		let serializer = self.into_serializer();
+		serializer.formatter.begin_object(serializer.writer)?;
+		Ok(ObjectSerializer {serializer, past_first: false})
+	}
+
+	#[inline]
+	pub fn array(self) -> Result<ArraySerializer<'a, 'w, F>, Error> {
+		let serializer = self.into_serializer();
+		serializer.formatter.begin_array(serializer.writer)?;
+		Ok(ArraySerializer {serializer, past_first: false})
+	}
+}
+
+impl<'a, 'w, F> Drop for ValueSerializer<'a, 'w, F>
+		where 'w: 'a, F: Formatter {
+	#[inline]
+	fn drop(&mut self) {
+		let Serializer {writer, formatter} = &mut *self.0;
+		let _ = formatter.write_scalar(*writer, &|writer| writer.write_str("null"));
+	}
+}
+
+/// Serializes the entries of an object, which is closed when
+/// [`end`](Self::end) is called or when dropped.
+pub struct ObjectSerializer<'a, 'w, F = Compact>
+		where 'w: 'a, F: Formatter {
+	serializer: &'a mut Serializer<'w, F>,
+	past_first: bool
+}
+
+impl<'a, 'w, F> ObjectSerializer<'a, 'w, F>
+		where 'w: 'a, F: Formatter {
+	#[inline]
+	pub fn entry(&mut self, key: &str) -> Result<ValueSerializer<'_, 'w, F>, Error> {
+		let Serializer {writer, formatter} = &mut *self.serializer;
+		formatter.begin_key(*writer, !replace(&mut self.past_first, true))?;
+		let escape = formatter.escape();
+		formatter.write_key(*writer, key, escape)?;
+		formatter.begin_value(*writer)?;
+		Ok(ValueSerializer(self.serializer))
+	}
+
+	#[inline]
+	fn close(&mut self) -> fmt::Result {
+		let Serializer {writer, formatter} = &mut *self.serializer;
+		formatter.end_object(*writer, !self.past_first)
+	}
+
+	#[inline]
+	pub fn field<T>(&mut self, key: &str, value: &T) -> Result<(), Error>
+			where T: Serialize + ?Sized {
+		value.serialize(self.entry(key)?)
+	}
+
+	#[inline]
+	pub fn end(self) -> Result<(), Error> {
+		let mut this = ManuallyDrop::new(self);
+		Ok(this.close()?)
+	}
+}
+
+impl<'a, 'w, F> Drop for ObjectSerializer<'a, 'w, F>
+		where 'w: 'a, F: Formatter {
+	#[inline]
+	fn drop(&mut self) {
+		let _ = self.close();
+	}
+}
+
+/// Serializes the elements of an array, which is closed when
+/// [`end`](Self::end) is called or when dropped.
+pub struct ArraySerializer<'a, 'w, F = Compact>
+		where 'w: 'a, F: Formatter {
+	serializer: &'a mut Serializer<'w, F>,
+	past_first: bool
+}
+
+impl<'a, 'w, F> ArraySerializer<'a, 'w, F>
+		where 'w: 'a, F: Formatter {
+	#[inline]
+	pub fn element(&mut self) -> Result<ValueSerializer<'_, 'w, F>, Error> {
+		let Serializer {writer, formatter} = &mut *self.serializer;
+		formatter.begin_element(*writer, !replace(&mut self.past_first, true))?;
+		Ok(ValueSerializer(self.serializer))
+	}
+
+	#[inline]
+	fn close(&mut self) -> fmt::Result {
+		let Serializer {writer, formatter} = &mut *self.serializer;
+		formatter.end_array(*writer, !self.past_first)
+	}
+
+	#[inline]
+	pub fn push<T>(&mut self, value: &T) -> Result<(), Error>
+			where T: Serialize + ?Sized {
+		value.serialize(self.element()?)
+	}
+
+	#[inline]
+	pub fn end(self) -> Result<(), Error> {
+		let mut this = ManuallyDrop::new(self);
+		Ok(this.close()?)
+	}
+}
+
+impl<'a, 'w, F> Drop for ArraySerializer<'a, 'w, F>
+		where 'w: 'a, F: Formatter {
+	#[inline]
+	fn drop(&mut self) {
+		let _ = self.close();
+	}
+}
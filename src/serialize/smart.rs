@@ -0,0 +1,318 @@
+//! Formatting that keeps containers on one line when they fit, like prettier
+//! does for JSON.
+
+use super::{
+	format::{Escape, Formatter, Indent, Newline},
+	r#trait::Serialize, reformat, to_string_with, write_string, Error
+};
+use std::fmt::{Result as FMTResult, Write};
+
+/// Columns a tab counts for when measuring lines.
+const TAB_WIDTH: usize = 4;
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct SmartConfig {
+	/// Columns a line may take up before containers on it are broken.
+	pub width: usize,
+	/// Indentation of each level of nesting in broken containers.
+	pub indent: Indent,
+	pub newline: Newline,
+	/// Whether the values of broken objects are padded to line up.
+	pub align_values: bool,
+	/// Whether object keys are sorted rather than kept in the order they
+	/// are written.
+	pub sort_keys: bool,
+	pub escape: Escape
+}
+
+impl Default for SmartConfig {
+	fn default() -> Self {
+		Self {
+			width: 80,
+			indent: Indent::Tabs,
+			newline: Newline::Lf,
+			align_values: false,
+			sort_keys: false,
+			escape: Escape::Minimal
+		}
+	}
+}
+
+/// Serializes a value, keeping containers on one line when they fit.
+#[inline]
+pub fn to_string_smart<T>(value: &T, config: &SmartConfig) -> Result<String, Error>
+		where T: Serialize + ?Sized {
+	to_string_with(value, Smart::new(*config))
+}
+
+/// Rewrites a JSON document, keeping containers on one line when they fit.
+#[inline]
+pub fn reformat_smart(input: &str, config: &SmartConfig) -> Result<String, Error> {
+	reformat(input, Smart::new(*config))
+}
+
+/// Keeps containers on one line when they fit. Whether one does depends on
+/// everything in it, so the outermost value is collected before any of it is
+/// written.
+#[derive(Clone, Debug, Default)]
+pub struct Smart {
+	config: SmartConfig,
+	/// Containers being collected, the innermost last.
+	open: Vec<Open>
+}
+
+impl Smart {
+	#[inline]
+	pub fn new(config: SmartConfig) -> Self {
+		Self {config, open: Vec::new()}
+	}
+
+	#[inline]
+	pub fn config(&self) -> &SmartConfig {
+		&self.config
+	}
+
+	/// Adds a complete value to the innermost container, or writes it out if
+	/// it is the outermost one.
+	fn complete(&mut self, writer: &mut dyn Write, node: Node) -> FMTResult {
+		match self.open.last_mut() {
+			None => Layout {config: &self.config, output: writer}.node(&node, 0, 0, 0),
+			Some(Open::Array(elements)) => {
+				elements.push(node);
+				Ok(())
+			},
+			Some(Open::Object {entries, key}) => {
+				let key = key.take().expect("object value written without a key");
+				entries.push((key, node));
+				Ok(())
+			}
+		}
+	}
+}
+
+impl Formatter for Smart {
+	#[inline]
+	fn begin_object(&mut self, _writer: &mut dyn Write) -> FMTResult {
+		self.open.push(Open::Object {entries: Vec::new(), key: None});
+		Ok(())
+	}
+
+	#[inline]
+	fn begin_key(&mut self, _writer: &mut dyn Write, _first: bool) -> FMTResult {
+		Ok(())
+	}
+
+	fn write_key(&mut self, _writer: &mut dyn Write, key: &str, escape: Escape) -> FMTResult {
+		let mut text = String::new();
+		write_string(&mut text, key, escape)?;
+		if let Some(Open::Object {key: pending, ..}) = self.open.last_mut() {
+			*pending = Some(Key {name: key.to_owned(), text});
+		}
+		Ok(())
+	}
+
+	#[inline]
+	fn begin_value(&mut self, _writer: &mut dyn Write) -> FMTResult {
+		Ok(())
+	}
+
+	fn end_object(&mut self, writer: &mut dyn Write, _empty: bool) -> FMTResult {
+		let Some(Open::Object {mut entries, ..}) = self.open.pop() else {
+			panic!("object ended while not in one")
+		};
+		if self.config.sort_keys {
+			entries.sort_by(|(a, _), (b, _)| a.name.cmp(&b.name));
+		}
+		let entries = entries.into_iter().map(|(key, value)| (key.text, value)).collect();
+		self.complete(writer, Node::new(NodeKind::Object(entries)))
+	}
+
+	#[inline]
+	fn begin_array(&mut self, _writer: &mut dyn Write) -> FMTResult {
+		self.open.push(Open::Array(Vec::new()));
+		Ok(())
+	}
+
+	#[inline]
+	fn begin_element(&mut self, _writer: &mut dyn Write, _first: bool) -> FMTResult {
+		Ok(())
+	}
+
+	fn end_array(&mut self, writer: &mut dyn Write, _empty: bool) -> FMTResult {
+		let Some(Open::Array(elements)) = self.open.pop() else {
+			panic!("array ended while not in one")
+		};
+		self.complete(writer, Node::new(NodeKind::Array(elements)))
+	}
+
+	fn write_scalar(&mut self, writer: &mut dyn Write, scalar: &dyn Fn(&mut dyn Write) -> FMTResult)
+			-> FMTResult {
+		let mut text = String::new();
+		scalar(&mut text)?;
+		self.complete(writer, Node::new(NodeKind::Scalar(text)))
+	}
+
+	#[inline]
+	fn escape(&self) -> Escape {
+		self.config.escape
+	}
+}
+
+/// A container whose contents are still being written.
+#[derive(Clone, Debug)]
+enum Open {
+	Array(Vec<Node>),
+	Object {
+		entries: Vec<(Key, Node)>,
+		/// Key of the entry whose value is being written.
+		key: Option<Key>
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Key {
+	name: String,
+	/// The key serialized.
+	text: String
+}
+
+/// A value along with the width it takes up on a single line.
+#[derive(Clone, Debug)]
+struct Node {
+	kind: NodeKind,
+	width: usize
+}
+
+#[derive(Clone, Debug)]
+enum NodeKind {
+	/// Serialized text of a string, number, boolean or null.
+	Scalar(String),
+	Array(Vec<Node>),
+	/// Entries with their keys already serialized.
+	Object(Vec<(String, Node)>)
+}
+
+impl Node {
+	fn new(kind: NodeKind) -> Self {
+		// Flat arrays are written `[1, 2]` and flat objects `{ "a": 1 }`.
+		let width = match &kind {
+			NodeKind::Scalar(text) => text.chars().count(),
+			NodeKind::Array(elements) if elements.is_empty() => 2,
+			NodeKind::Array(elements) => elements.iter()
+				.map(|element| element.width)
+				.sum::<usize>() + 2 * elements.len(),
+			NodeKind::Object(entries) if entries.is_empty() => 2,
+			NodeKind::Object(entries) => entries.iter()
+				.map(|(key, value)| key.chars().count() + 2 + value.width)
+				.sum::<usize>() + 2 * entries.len() + 2
+		};
+
+		Self {kind, width}
+	}
+}
+
+struct Layout<'a> {
+	config: &'a SmartConfig,
+	output: &'a mut dyn Write
+}
+
+impl Layout<'_> {
+	fn indent_width(&self, depth: usize) -> usize {
+		match self.config.indent {
+			Indent::Spaces(spaces) => spaces * depth,
+			Indent::Tabs => TAB_WIDTH * depth
+		}
+	}
+
+	fn line(&mut self, depth: usize) -> FMTResult {
+		self.output.write_str(self.config.newline.as_str())?;
+		match self.config.indent {
+			Indent::Spaces(spaces) => write!(self.output, "{:1$}", "", spaces * depth),
+			Indent::Tabs => (0..depth).try_for_each(|_| self.output.write_char('\t'))
+		}
+	}
+
+	/// Writes a node starting at `column`, followed by `trailing` columns
+	/// that have to fit on the same line.
+	fn node(&mut self, node: &Node, depth: usize, column: usize, trailing: usize)
+			-> FMTResult {
+		if column + node.width + trailing <= self.config.width {
+			return self.flat(node)
+		}
+
+		match &node.kind {
+			NodeKind::Scalar(text) => self.output.write_str(text),
+			NodeKind::Array(elements) if elements.is_empty() => self.flat(node),
+			NodeKind::Object(entries) if entries.is_empty() => self.flat(node),
+
+			NodeKind::Array(elements) => {
+				self.output.write_char('[')?;
+				for (index, element) in elements.iter().enumerate() {
+					let last = index + 1 == elements.len();
+					self.line(depth + 1)?;
+					self.node(element, depth + 1, self.indent_width(depth + 1),
+						usize::from(!last))?;
+					if !last {
+						self.output.write_char(',')?;
+					}
+				}
+				self.line(depth)?;
+				self.output.write_char(']')
+			},
+
+			NodeKind::Object(entries) => {
+				let key_width = match self.config.align_values {
+					true => entries.iter().map(|(key, _)| key.chars().count()).max()
+						.unwrap_or_default(),
+					false => 0
+				};
+
+				self.output.write_char('{')?;
+				for (index, (key, value)) in entries.iter().enumerate() {
+					let last = index + 1 == entries.len();
+					let key_length = key.chars().count();
+					let padding = key_width.saturating_sub(key_length);
+
+					self.line(depth + 1)?;
+					write!(self.output, "{}: {:2$}", key, "", padding)?;
+					self.node(value, depth + 1,
+						self.indent_width(depth + 1) + key_length + 2 + padding,
+						usize::from(!last))?;
+					if !last {
+						self.output.write_char(',')?;
+					}
+				}
+				self.line(depth)?;
+				self.output.write_char('}')
+			}
+		}
+	}
+
+	fn flat(&mut self, node: &Node) -> FMTResult {
+		match &node.kind {
+			NodeKind::Scalar(text) => self.output.write_str(text),
+			NodeKind::Array(elements) => {
+				self.output.write_char('[')?;
+				for (index, element) in elements.iter().enumerate() {
+					if index != 0 {
+						self.output.write_str(", ")?;
+					}
+					self.flat(element)?;
+				}
+				self.output.write_char(']')
+			},
+			NodeKind::Object(entries) if entries.is_empty() => self.output.write_str("{}"),
+			NodeKind::Object(entries) => {
+				self.output.write_str("{ ")?;
+				for (index, (key, value)) in entries.iter().enumerate() {
+					if index != 0 {
+						self.output.write_str(", ")?;
+					}
+					write!(self.output, "{}: ", key)?;
+					self.flat(value)?;
+				}
+				self.output.write_str(" }")
+			}
+		}
+	}
+}
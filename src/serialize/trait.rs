@@ -0,0 +1,487 @@
+use super::{format::Formatter, Error, ValueSerializer};
+use crate::deserialize::r#trait::FromStrValue;
+use std::{
+	borrow::Cow,
+	cell::{Cell, RefCell},
+	collections::{
+		BinaryHeap, BTreeMap, BTreeSet, HashMap, HashSet, LinkedList, VecDeque
+	},
+	ffi::{OsStr, OsString},
+	fmt::Display,
+	marker::PhantomData,
+	net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+	num::{
+		NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize,
+		NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+		Wrapping
+	},
+	path::{Path, PathBuf},
+	rc::Rc,
+	sync::{Arc, Mutex, RwLock},
+	time::Duration
+};
+
+pub use qj_derive::{Serialize, SerializeKey};
+
+pub trait Serialize {
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+		where F: Formatter;
+}
+
+/// A type that can be written as the name of an object entry, such as the
+/// keys of a map.
+pub trait SerializeKey {
+	fn serialize_key(&self) -> Cow<'_, str>;
+}
+
+macro_rules! impl_integers {
+	($($integer:ty => $method:ident),*) => {
+		$(
+			impl Serialize for $integer {
+				#[inline]
+				fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+						where F: Formatter {
+					value.$method(*self as _)
+				}
+			}
+
+			impl SerializeKey for $integer {
+				#[inline]
+				fn serialize_key(&self) -> Cow<'_, str> {
+					Cow::Owned(self.to_string())
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_non_zero_integers {
+	($($non_zero:ty),*) => {
+		$(
+			impl Serialize for $non_zero {
+				#[inline]
+				fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+						where F: Formatter {
+					self.get().serialize(value)
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_display {
+	($($type:ty),*) => {
+		$(
+			impl Serialize for $type {
+				#[inline]
+				fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+						where F: Formatter {
+					value.string(&self.to_string())
+				}
+			}
+
+			impl SerializeKey for $type {
+				#[inline]
+				fn serialize_key(&self) -> Cow<'_, str> {
+					Cow::Owned(self.to_string())
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_strings {
+	($($string:ty),*) => {
+		$(
+			impl Serialize for $string {
+				#[inline]
+				fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+						where F: Formatter {
+					value.string(self)
+				}
+			}
+
+			impl SerializeKey for $string {
+				#[inline]
+				fn serialize_key(&self) -> Cow<'_, str> {
+					Cow::Borrowed(self)
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_wrappers {
+	($($wrapper:ident),*) => {
+		$(
+			impl<T> Serialize for $wrapper<T>
+					where T: Serialize + ?Sized {
+				#[inline]
+				fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+						where F: Formatter {
+					(**self).serialize(value)
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_sequences {
+	($($sequence:ident<T $(, $hasher:ident)?>),*) => {
+		$(
+			impl<T $(, $hasher)?> Serialize for $sequence<T $(, $hasher)?>
+					where T: Serialize {
+				#[inline]
+				fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+						where F: Formatter {
+					serialize_array(self, value)
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_maps {
+	($($map:ident<K, V $(, $hasher:ident)?>),*) => {
+		$(
+			impl<K, V $(, $hasher)?> Serialize for $map<K, V $(, $hasher)?>
+					where K: SerializeKey, V: Serialize {
+				#[inline]
+				fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+						where F: Formatter {
+					let mut object = value.object()?;
+					for (key, value) in self {
+						object.field(&key.serialize_key(), value)?;
+					}
+					object.end()
+				}
+			}
+		)*
+	}
+}
+
+macro_rules! impl_tuples {
+	(@impl $($index:tt $type:ident)+) => {
+		impl<$($type,)+> Serialize for ($($type,)+)
+				where $($type: Serialize,)+ {
+			#[inline]
+			fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+					where F: Formatter {
+				let mut array = value.array()?;
+				$(array.push(&self.$index)?;)+
+				array.end()
+			}
+		}
+	};
+
+	([$($done:tt)*] $index:tt $type:ident $($rest:tt)*) => {
+		impl_tuples!(@impl $($done)* $index $type);
+		impl_tuples!([$($done)* $index $type] $($rest)*);
+	};
+
+	([$($done:tt)*]) => {}
+}
+
+#[inline]
+fn serialize_array<'a, I, T, F>(elements: I, value: ValueSerializer<'_, '_, F>)
+		-> Result<(), Error>
+			where I: IntoIterator<Item = &'a T>, T: Serialize + 'a, F: Formatter {
+	let mut array = value.array()?;
+	for element in elements {
+		array.push(element)?;
+	}
+	array.end()
+}
+
+impl_integers! {
+	u8 => u64, u16 => u64, u32 => u64, u64 => u64, usize => u64, u128 => u128,
+	i8 => i64, i16 => i64, i32 => i64, i64 => i64, isize => i64, i128 => i128
+}
+
+impl_non_zero_integers! {
+	NonZeroU8, NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU128, NonZeroUsize,
+	NonZeroI8, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI128, NonZeroIsize
+}
+
+impl Serialize for f32 {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		value.f32(*self)
+	}
+}
+
+impl Serialize for f64 {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		value.f64(*self)
+	}
+}
+
+impl_strings! {str, String}
+
+impl SerializeKey for Cow<'_, str> {
+	#[inline]
+	fn serialize_key(&self) -> Cow<'_, str> {
+		Cow::Borrowed(self)
+	}
+}
+
+impl<T> SerializeKey for &T
+		where T: SerializeKey + ?Sized {
+	#[inline]
+	fn serialize_key(&self) -> Cow<'_, str> {
+		(**self).serialize_key()
+	}
+}
+
+impl<T> SerializeKey for Box<T>
+		where T: SerializeKey + ?Sized {
+	#[inline]
+	fn serialize_key(&self) -> Cow<'_, str> {
+		(**self).serialize_key()
+	}
+}
+
+impl<T> SerializeKey for Rc<T>
+		where T: SerializeKey + ?Sized {
+	#[inline]
+	fn serialize_key(&self) -> Cow<'_, str> {
+		(**self).serialize_key()
+	}
+}
+
+impl<T> SerializeKey for Arc<T>
+		where T: SerializeKey + ?Sized {
+	#[inline]
+	fn serialize_key(&self) -> Cow<'_, str> {
+		(**self).serialize_key()
+	}
+}
+
+impl_display! {
+	char, IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6
+}
+
+impl Serialize for bool {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		value.boolean(*self)
+	}
+}
+
+impl SerializeKey for bool {
+	#[inline]
+	fn serialize_key(&self) -> Cow<'_, str> {
+		Cow::Borrowed(if *self {"true"} else {"false"})
+	}
+}
+
+impl<T> Serialize for FromStrValue<T>
+		where T: Display {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		value.string(&self.0.to_string())
+	}
+}
+
+impl<T> SerializeKey for FromStrValue<T>
+		where T: Display {
+	#[inline]
+	fn serialize_key(&self) -> Cow<'_, str> {
+		Cow::Owned(self.0.to_string())
+	}
+}
+
+impl Serialize for Path {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		match self.to_str() {
+			Some(path) => value.string(path),
+			None => Err(Error::custom("path is not valid UTF-8"))
+		}
+	}
+}
+
+impl Serialize for PathBuf {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		self.as_path().serialize(value)
+	}
+}
+
+impl Serialize for OsStr {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		match self.to_str() {
+			Some(string) => value.string(string),
+			None => Err(Error::custom("OS string is not valid UTF-8"))
+		}
+	}
+}
+
+impl Serialize for OsString {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		self.as_os_str().serialize(value)
+	}
+}
+
+impl Serialize for Duration {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		let mut object = value.object()?;
+		object.field("secs", &self.as_secs())?;
+		object.field("nanos", &self.subsec_nanos())?;
+		object.end()
+	}
+}
+
+impl Serialize for () {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		value.null()
+	}
+}
+
+impl<T> Serialize for PhantomData<T>
+		where T: ?Sized {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		value.null()
+	}
+}
+
+impl_wrappers! {Box, Rc, Arc}
+
+impl<T> Serialize for &T
+		where T: Serialize + ?Sized {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		(**self).serialize(value)
+	}
+}
+
+impl<T> Serialize for &mut T
+		where T: Serialize + ?Sized {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		(**self).serialize(value)
+	}
+}
+
+impl<T> Serialize for Cow<'_, T>
+		where T: Serialize + ToOwned + ?Sized {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		(**self).serialize(value)
+	}
+}
+
+impl<T> Serialize for Wrapping<T>
+		where T: Serialize {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		self.0.serialize(value)
+	}
+}
+
+impl<T> Serialize for Cell<T>
+		where T: Serialize + Copy {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		self.get().serialize(value)
+	}
+}
+
+impl<T> Serialize for RefCell<T>
+		where T: Serialize + ?Sized {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		match self.try_borrow() {
+			Ok(borrowed) => borrowed.serialize(value),
+			Err(_) => Err(Error::custom("RefCell is mutably borrowed"))
+		}
+	}
+}
+
+impl<T> Serialize for Mutex<T>
+		where T: Serialize + ?Sized {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		match self.lock() {
+			Ok(locked) => locked.serialize(value),
+			Err(_) => Err(Error::custom("Mutex is poisoned"))
+		}
+	}
+}
+
+impl<T> Serialize for RwLock<T>
+		where T: Serialize + ?Sized {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		match self.read() {
+			Ok(locked) => locked.serialize(value),
+			Err(_) => Err(Error::custom("RwLock is poisoned"))
+		}
+	}
+}
+
+impl<T> Serialize for Option<T>
+		where T: Serialize {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		match self {
+			Some(inner) => inner.serialize(value),
+			None => value.null()
+		}
+	}
+}
+
+impl<T> Serialize for [T]
+		where T: Serialize {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		serialize_array(self, value)
+	}
+}
+
+impl<T, const N: usize> Serialize for [T; N]
+		where T: Serialize {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), Error>
+			where F: Formatter {
+		serialize_array(self, value)
+	}
+}
+
+impl_sequences! {
+	Vec<T>, VecDeque<T>, LinkedList<T>, BinaryHeap<T>, HashSet<T, S>, BTreeSet<T>
+}
+
+impl_tuples! {[]
+	0 T0 1 T1 2 T2 3 T3 4 T4 5 T5 6 T6 7 T7 8 T8 9 T9 10 T10 11 T11 12 T12
+	13 T13 14 T14 15 T15
+}
+
+impl_maps! {HashMap<K, V, S>, BTreeMap<K, V>}
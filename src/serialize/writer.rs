@@ -0,0 +1,213 @@
+//! Writing a document piece by piece, for output too large to build first.
+
+use super::{format::{Compact, Formatter}, r#trait::Serialize, Error, IoWriter, Serializer};
+use std::{fmt::{self, Display}, io, mem::replace};
+
+#[derive(Debug)]
+pub enum WriterError {
+	/// The `io::Write` being written to failed.
+	Io(io::Error),
+	/// A value passed to [`JsonWriter::value`] could not be serialized.
+	Serialize(Error),
+	/// A key was written outside of an object, or right after another key.
+	UnexpectedKey,
+	/// A value was written in an object without a key before it.
+	ExpectedKey,
+	/// A container was ended that is not the innermost one open, or an object
+	/// was ended right after a key.
+	UnexpectedEnd,
+	/// A value was written after the document was already complete.
+	TrailingValue,
+	/// The document was finished before it was complete.
+	Incomplete,
+	/// An earlier call failed partway through writing, leaving the output
+	/// unusable.
+	Poisoned
+}
+
+impl From<io::Error> for WriterError {
+	#[inline]
+	fn from(error: io::Error) -> Self {
+		Self::Io(error)
+	}
+}
+
+impl Display for WriterError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Io(error) => error.fmt(f),
+			Self::Serialize(error) => error.fmt(f),
+			Self::UnexpectedKey => f.write_str("key outside of an object or after another key"),
+			Self::ExpectedKey => f.write_str("value in an object without a key"),
+			Self::UnexpectedEnd => f.write_str("end of a container that is not open"),
+			Self::TrailingValue => f.write_str("value after the end of the document"),
+			Self::Incomplete => f.write_str("document is incomplete"),
+			Self::Poisoned => f.write_str("writer failed earlier")
+		}
+	}
+}
+
+impl std::error::Error for WriterError {}
+
+enum Container {
+	Object {past_first: bool, has_key: bool},
+	Array {past_first: bool}
+}
+
+/// Writes a document to an `io::Write` as it is described, one key, value or
+/// container boundary at a time.
+///
+/// Misuse, such as a value in an object without a key, is reported before
+/// anything is written, so the writer remains usable after it.
+pub struct JsonWriter<W, F = Compact>
+		where W: io::Write, F: Formatter {
+	writer: IoWriter<W>,
+	formatter: F,
+	containers: Vec<Container>,
+	complete: bool,
+	poisoned: bool
+}
+
+impl<W> JsonWriter<W>
+		where W: io::Write {
+	#[inline]
+	pub fn new(writer: W) -> Self {
+		Self::with_formatter(writer, Compact)
+	}
+}
+
+impl<W, F> JsonWriter<W, F>
+		where W: io::Write, F: Formatter {
+	#[inline]
+	pub fn with_formatter(writer: W, formatter: F) -> Self {
+		Self {
+			writer: IoWriter {writer, error: None},
+			formatter,
+			containers: Vec::new(),
+			complete: false,
+			poisoned: false
+		}
+	}
+
+	/// Runs a write, poisoning the writer if it fails.
+	fn write<R>(&mut self, write: impl FnOnce(&mut Self) -> Result<R, Error>)
+			-> Result<R, WriterError> {
+		write(self).map_err(|error| {
+			self.poisoned = true;
+			match self.writer.error.take() {
+				Some(error) => WriterError::Io(error),
+				None => WriterError::Serialize(error)
+			}
+		})
+	}
+
+	fn check_poisoned(&self) -> Result<(), WriterError> {
+		if self.poisoned {Err(WriterError::Poisoned)} else {Ok(())}
+	}
+
+	/// Checks that a value may be written now and writes what precedes it.
+	fn begin_value(&mut self) -> Result<(), WriterError> {
+		self.check_poisoned()?;
+		match self.containers.last_mut() {
+			None if self.complete => Err(WriterError::TrailingValue),
+			None => Ok(()),
+			Some(Container::Object {has_key: false, ..}) => Err(WriterError::ExpectedKey),
+			Some(Container::Object {has_key, ..}) => {
+				*has_key = false;
+				Ok(())
+			},
+			Some(Container::Array {past_first}) => {
+				let first = !replace(past_first, true);
+				self.write(|this| Ok(this.formatter.begin_element(&mut this.writer, first)?))
+			}
+		}
+	}
+
+	#[inline]
+	fn end_value(&mut self) {
+		if self.containers.is_empty() {
+			self.complete = true;
+		}
+	}
+
+	/// Writes a complete value.
+	pub fn value<T>(&mut self, value: &T) -> Result<(), WriterError>
+			where T: Serialize + ?Sized {
+		self.begin_value()?;
+		self.write(|this| value.serialize(
+			Serializer::with_formatter(&mut this.writer, &mut this.formatter).value()))?;
+		self.end_value();
+		Ok(())
+	}
+
+	pub fn begin_object(&mut self) -> Result<(), WriterError> {
+		self.begin_value()?;
+		self.write(|this| Ok(this.formatter.begin_object(&mut this.writer)?))?;
+		self.containers.push(Container::Object {past_first: false, has_key: false});
+		Ok(())
+	}
+
+	/// Writes the key of the next entry of the innermost object, whose value
+	/// has to be written next.
+	pub fn key(&mut self, key: &str) -> Result<(), WriterError> {
+		self.check_poisoned()?;
+		let first = match self.containers.last_mut() {
+			Some(Container::Object {past_first, has_key: has_key @ false}) => {
+				*has_key = true;
+				!replace(past_first, true)
+			},
+			_ => return Err(WriterError::UnexpectedKey)
+		};
+
+		self.write(|this| {
+			this.formatter.begin_key(&mut this.writer, first)?;
+			let escape = this.formatter.escape();
+			this.formatter.write_key(&mut this.writer, key, escape)?;
+			Ok(this.formatter.begin_value(&mut this.writer)?)
+		})
+	}
+
+	pub fn end_object(&mut self) -> Result<(), WriterError> {
+		self.check_poisoned()?;
+		let empty = match self.containers.last() {
+			Some(Container::Object {past_first, has_key: false}) => !past_first,
+			_ => return Err(WriterError::UnexpectedEnd)
+		};
+
+		self.containers.pop();
+		self.write(|this| Ok(this.formatter.end_object(&mut this.writer, empty)?))?;
+		self.end_value();
+		Ok(())
+	}
+
+	pub fn begin_array(&mut self) -> Result<(), WriterError> {
+		self.begin_value()?;
+		self.write(|this| Ok(this.formatter.begin_array(&mut this.writer)?))?;
+		self.containers.push(Container::Array {past_first: false});
+		Ok(())
+	}
+
+	pub fn end_array(&mut self) -> Result<(), WriterError> {
+		self.check_poisoned()?;
+		let empty = match self.containers.last() {
+			Some(Container::Array {past_first}) => !past_first,
+			_ => return Err(WriterError::UnexpectedEnd)
+		};
+
+		self.containers.pop();
+		self.write(|this| Ok(this.formatter.end_array(&mut this.writer, empty)?))?;
+		self.end_value();
+		Ok(())
+	}
+
+	/// Checks that the document is complete, flushes the writer and returns it.
+	pub fn finish(mut self) -> Result<W, WriterError> {
+		self.check_poisoned()?;
+		if !self.complete {
+			return Err(WriterError::Incomplete)
+		}
+
+		self.writer.writer.flush()?;
+		Ok(self.writer.writer)
+	}
+}
@@ -1,117 +1,1080 @@
-use super::from_str;
-use maplit::hashmap;
-use serde::Deserialize;
+use crate::deserialize::{
+	error::{Coercion, Duplicates, ErrorContext, ErrorKind, FloatRange, KeyKind},
+	r#trait::Deserialize,
+	Deserializer, ValueDeserializer
+};
 use std::{borrow::Cow, collections::HashMap as STDHashMap, vec::Vec as STDVec};
 
-macro_rules! manufacture {
-	() => {};
-	($name:ident<$T:ty>($input:literal, $expected:expr); $($rest:tt)*) => {
-		#[test]
-		fn $name() {
-			const INPUT: &str = $input;
-			let test: $T = from_str(INPUT).unwrap();
-			let expected: $T = $expected;
-			assert_eq!(test, expected);
-		}
+#[cfg(feature = "serde")]
+mod serde;
+
+/// Error context with configurable policies, recording what is reported.
+#[derive(Debug, Default)]
+struct Policy {
+	coercion: Coercion,
+	float_range: FloatRange,
+	duplicates: Duplicates,
+	errors: STDVec<String>,
+	warnings: STDVec<String>
+}
+
+impl<'s> ErrorContext<'s> for Policy {
+	fn report_unknown<M>(&mut self, message: M)
+			where M: ToString {
+		self.errors.push(message.to_string());
+	}
+
+	fn report_warning<M>(&mut self, message: M)
+			where M: ToString {
+		self.warnings.push(message.to_string());
+	}
 
-		manufacture! {$($rest)*}
+	fn coercion(&self) -> Coercion {
+		self.coercion
+	}
+
+	fn float_range(&self) -> FloatRange {
+		self.float_range
+	}
+
+	fn duplicates(&self) -> Duplicates {
+		self.duplicates
 	}
 }
 
-type HashMap = STDHashMap<&'static str, &'static str>;
-type Vec = STDVec<&'static str>;
+/// Deserializes a whole document under a policy, expecting valid syntax.
+fn deserialize_with<'s, T>(input: &'s str, policy: &mut Policy) -> Option<T>
+		where T: Deserialize<'s, Policy> {
+	let mut deserializer = Deserializer::new(input);
+	let value = ValueDeserializer::new(&mut deserializer)
+		.and_then(|value| T::deserialize(value, policy))
+		.unwrap();
+	deserializer.finish().unwrap();
+	value
+}
 
-#[derive(Debug, Deserialize, PartialEq)]
-struct Complex<'s> {
-	#[serde(borrow)]
-	what_if: ComplexA<'s>,
-	we_put: ComplexB,
-	our: ComplexC
+/// Deserializes a whole document over `place` under a policy, expecting valid
+/// syntax.
+fn deserialize_in_place_with<'s, T>(place: &mut T, input: &'s str, policy: &mut Policy) -> bool
+		where T: Deserialize<'s, Policy> {
+	let mut deserializer = Deserializer::new(input);
+	let success = ValueDeserializer::new(&mut deserializer)
+		.and_then(|value| place.deserialize_in_place(value, policy))
+		.unwrap();
+	deserializer.finish().unwrap();
+	success
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
-#[serde(untagged)]
-enum ComplexA<'s> {
-	ThisIsAVariant,
-	ThisIsAlsoAVariant(Cow<'s, str>, u32),
-	IfYouHaveMoreCreativeNamesPleaseMakeAPullRequest {
-		minecraft: i64,
-		beds: String,
-		together: &'s str
+type Native = STDHashMap<String, STDVec<u8>>;
+
+#[test]
+fn test_from_str_collects_errors() {
+	let errors = crate::from_str::<Native>("{\"name\":1,\"values\":[1,\"2\",3]}")
+		.unwrap_err()
+		.into_errors();
+	let paths: STDVec<_> = errors.iter().map(|error| error.path.clone()).collect();
+	assert_eq!(paths, vec![
+		vec![KeyKind::Object("name".into())],
+		vec![KeyKind::Object("values".into()), KeyKind::Array(1)]
+	]);
+}
+
+#[test]
+fn test_from_str_syntax_error_path() {
+	let errors = crate::from_str::<Native>("{\"name\":[],\"values\":[1,}")
+		.unwrap_err()
+		.into_errors();
+	assert_eq!(errors.len(), 1);
+	assert!(matches!(errors[0].kind, ErrorKind::Syntax(_)));
+	assert_eq!(errors[0].path, vec![KeyKind::Object("values".into())]);
+}
+
+#[test]
+fn test_from_str_trailing_content() {
+	use crate::deserialize::{error::Errors, from_str_default};
+
+	for errors in [crate::from_str::<u32>("1 2").map(drop), crate::from_str::<bool>("true garbage").map(drop)] {
+		let errors = errors.unwrap_err().into_errors();
+		assert_eq!(errors.len(), 1);
+		assert!(matches!(errors[0].kind, ErrorKind::Syntax(_)));
 	}
+	assert_eq!(crate::from_str::<u32>(" 1 \n").unwrap(), 1);
+
+	// Only `from_str` checks for the end of the input.
+	let (result, errors) = from_str_default::<u32, Errors>("1 2");
+	assert_eq!((result.unwrap(), errors.is_empty()), (Some(1), true));
 }
 
-#[derive(Debug, Deserialize, PartialEq)]
-struct ComplexB(String, u8, String);
+#[test]
+fn test_string_escapes() {
+	assert_eq!(crate::from_str::<String>("\"\\u00e9\"").unwrap(), "é");
+	assert_eq!(crate::from_str::<String>("\"a\\ud83d\\ude00b\"").unwrap(), "a😀b");
+	assert_eq!(crate::from_str::<String>("\"end\\n\"").unwrap(), "end\n");
+	assert_eq!(crate::from_str::<String>("\"\\u0041\"").unwrap(), "A");
+	for input in ["\"\\ud83d\"", "\"\\ude00\"", "\"\\ud83dx\"", "\"\\u00g0\"", "\"\\"] {
+		let errors = crate::from_str::<String>(input).unwrap_err().into_errors();
+		assert!(matches!(errors[0].kind, ErrorKind::Syntax(_)), "{}", input);
+	}
+}
 
-#[derive(Debug, Deserialize, PartialEq)]
-struct ComplexC;
+#[test]
+fn test_coercion() {
+	let mut strict = Policy::default();
+	assert_eq!(deserialize_with::<u32>("\"42\"", &mut strict), None);
+	assert_eq!(deserialize_with::<bool>("1", &mut strict), None);
+	assert_eq!(deserialize_with::<String>("42", &mut strict), None);
+	assert_eq!(strict.errors, ["expected a number, found a string",
+		"expected a boolean, found a number", "expected a string, found a number"]);
+	assert!(strict.warnings.is_empty());
 
-manufacture! {
-	test_deserialize_float_1<f64>("-69.0", -69.0);
-	test_deserialize_float_2<f64>("-123.456e7", -123.456e7);
-	test_deserialize_float_3<f64>("-420", -420.);
-	test_deserialize_float_4<f64>("1337.0", 1337.);
-	test_deserialize_float_5<f64>("12345", 12345.);
-	test_deserialize_float_6<f64>("1e20", 1e20);
+	let mut lenient = Policy {coercion: Coercion::Lenient, ..Default::default()};
+	assert_eq!(deserialize_with::<u32>("\"42\"", &mut lenient), Some(42));
+	assert_eq!(deserialize_with::<i64>("\"-7\"", &mut lenient), Some(-7));
+	assert_eq!(deserialize_with::<f64>("\"1.5e1\"", &mut lenient), Some(15.));
+	assert_eq!(deserialize_with::<bool>("\"true\"", &mut lenient), Some(true));
+	assert_eq!(deserialize_with::<bool>("0", &mut lenient), Some(false));
+	assert_eq!(deserialize_with::<String>("-1.50", &mut lenient).as_deref(), Some("-1.50"));
+	assert_eq!(deserialize_with::<String>("false", &mut lenient).as_deref(), Some("false"));
+	assert!(lenient.errors.is_empty());
+	assert_eq!(lenient.warnings.len(), 7);
+	assert_eq!(lenient.warnings[0], "coerced a string into a number");
 
-	test_deserialize_bool_1<bool>("true", true);
-	test_deserialize_bool_2<bool>("false", false);
+	assert_eq!(deserialize_with::<u32>("\"4 2\"", &mut lenient), None);
+	assert_eq!(deserialize_with::<u32>("\"\"", &mut lenient), None);
+	assert_eq!(deserialize_with::<bool>("\"yes\"", &mut lenient), None);
+	assert_eq!(deserialize_with::<bool>("2", &mut lenient), None);
+	assert_eq!(deserialize_with::<u32>("[]", &mut lenient), None);
+	assert_eq!(lenient.errors.len(), 5);
+	assert_eq!(lenient.warnings.len(), 7);
+}
 
-	test_deserialize_map_none<HashMap>("{}", hashmap! {});
-	test_deserialize_map_single<HashMap>(
-		"{\"hello\":\"there\"}",
-		hashmap! {"hello" => "there"}
-	);
-	test_deserialize_map_plural<HashMap>(
-		"{\"hello\":\"there\",\"there\":\"hello\"}",
-		hashmap! {"hello" => "there", "there" => "hello"}
-	);
+#[test]
+fn test_report_unexpected_type() {
+	use crate::deserialize::error::JSONType;
 
-	test_deserialize_vec_none<Vec>("[]", vec![]);
-	test_deserialize_vec_single<Vec>("[\"hello\"]", vec!["hello"]);
-	test_deserialize_vec_plural<Vec>(
-		"[\"hello\",\"there\",\"how\"]",
-		vec!["hello", "there", "how"]
-	);
+	let mut policy = Policy::default();
+	policy.report_unexpected_type(JSONType::Null, &[JSONType::Object]);
+	policy.report_unexpected_type(JSONType::Null, &[JSONType::Object, JSONType::Array]);
+	policy.report_unexpected_type(JSONType::Null, &[JSONType::Object, JSONType::Array, JSONType::String]);
+	assert_eq!(policy.errors, [
+		"expected an object, found null",
+		"expected an object or array, found null",
+		"expected an object, array, or string, found null"
+	]);
+}
 
-	test_deserialize_complex_1<Complex>(
-		"{\"what_if\":null,\"we_put\":[\"this is data\",7,\"big data\"],\"our\":null}",
-		Complex {
-			what_if: ComplexA::ThisIsAVariant,
-			we_put: ComplexB("this is data".into(), 7, "big data".into()),
-			our: ComplexC
-		}
-	);
+#[test]
+fn test_object_entry_drop() {
+	let mut deserializer = Deserializer::new("{\"skip\": {\"a\": [1, \"}\"]}, \"keep\": \"yes\", \"rest\": [3]} ");
+	let mut object = match ValueDeserializer::new(&mut deserializer).unwrap() {
+		ValueDeserializer::Object(object) => object,
+		_ => panic!("expected an object")
+	};
 
-	test_deserialize_complex_2<Complex>(
-		"{\"what_if\":[\"such parse\",578924],\"we_put\":[\"this is data\",7,\"big data\"],\"our\":null}",
-		Complex {
-			what_if: ComplexA::ThisIsAlsoAVariant(Cow::Borrowed("such parse"), 578924),
-			we_put: ComplexB("this is data".into(), 7, "big data".into()),
-			our: ComplexC
-		}
-	);
+	drop(object.next_entry().unwrap().unwrap());
+	match object.next_entry().unwrap().unwrap().accept().unwrap() {
+		(name, ValueDeserializer::String(string)) =>
+			assert_eq!((name, string.accept().unwrap()), (Cow::Borrowed("keep"), Cow::Borrowed("yes"))),
+		_ => panic!("expected a string")
+	}
+	let (name, _) = object.next_entry().unwrap().unwrap().accept().unwrap();
+	assert_eq!(name, "rest");
+	assert!(object.next_entry().unwrap().is_none());
+	drop(object);
+	deserializer.finish().unwrap();
+}
+
+#[test]
+fn test_primitives() {
+	use std::{marker::PhantomData, num::{NonZeroI8, NonZeroU32, Wrapping}};
+
+	let mut policy = Policy::default();
+	assert_eq!(deserialize_with::<bool>("true", &mut policy), Some(true));
+	assert_eq!(deserialize_with::<char>("\"é\"", &mut policy), Some('é'));
+	assert_eq!(deserialize_with::<char>("\"\\ud83d\\ude00\"", &mut policy), Some('😀'));
+	assert_eq!(deserialize_with::<()>("null", &mut policy), Some(()));
+	assert_eq!(deserialize_with::<NonZeroU32>("7", &mut policy), NonZeroU32::new(7));
+	assert_eq!(deserialize_with::<NonZeroI8>("-128", &mut policy), NonZeroI8::new(-128));
+	assert_eq!(deserialize_with::<Wrapping<u8>>("255", &mut policy), Some(Wrapping(255)));
+	assert_eq!(deserialize_with::<PhantomData<str>>("null", &mut policy), Some(PhantomData));
+	assert_eq!(deserialize_with::<Box<str>>("\"a\\tb\"", &mut policy).as_deref(), Some("a\tb"));
+	assert!(policy.errors.is_empty());
+
+	assert_eq!(deserialize_with::<bool>("null", &mut policy), None);
+	assert_eq!(deserialize_with::<char>("\"ab\"", &mut policy), None);
+	assert_eq!(deserialize_with::<char>("\"\"", &mut policy), None);
+	assert_eq!(deserialize_with::<()>("0", &mut policy), None);
+	assert_eq!(deserialize_with::<NonZeroU32>("0", &mut policy), None);
+	assert_eq!(deserialize_with::<NonZeroI8>("-129", &mut policy), None);
+	assert_eq!(deserialize_with::<Wrapping<u8>>("256", &mut policy), None);
+	assert_eq!(deserialize_with::<PhantomData<str>>("{}", &mut policy), None);
+	assert_eq!(deserialize_with::<Box<str>>("[]", &mut policy), None);
+	assert_eq!(policy.errors, [
+		"expected a boolean, found null",
+		"expected a string containing a single character",
+		"expected a string containing a single character",
+		"expected null, found a number",
+		"value cannot be zero in target (a non-zero unsigned 32 bit integer)",
+		"value causes an integer overflow in target (a signed 8 bit integer)",
+		"value causes an integer overflow in target (an unsigned 8 bit integer)",
+		"expected null, found an object",
+		"expected a string, found an array"
+	]);
+}
+
+#[test]
+fn test_float_range() {
+	let mut error = Policy::default();
+	assert_eq!(deserialize_with::<f64>("1e308", &mut error), Some(1e308));
+	assert_eq!(deserialize_with::<f64>("0e999", &mut error), Some(0.));
+	assert_eq!(deserialize_with::<f32>("3.4e38", &mut error), Some(3.4e38));
+	assert!(error.errors.is_empty());
+	assert_eq!(deserialize_with::<f64>("1e400", &mut error), None);
+	assert_eq!(deserialize_with::<f64>("-1e-400", &mut error), None);
+	assert_eq!(deserialize_with::<f32>("-1e39", &mut error), None);
+	assert_eq!(deserialize_with::<f32>("1e-50", &mut error), None);
+	assert_eq!(error.errors, [
+		"value is too large to be represented by target (a 64 bit float)",
+		"value is too small to be represented by target (a 64 bit float) and would become zero",
+		"value is too large to be represented by target (a 32 bit float)",
+		"value is too small to be represented by target (a 32 bit float) and would become zero"
+	]);
+
+	let mut saturate = Policy {float_range: FloatRange::Saturate, ..Default::default()};
+	assert_eq!(deserialize_with::<f64>("1e400", &mut saturate), Some(f64::MAX));
+	assert_eq!(deserialize_with::<f64>("-1e400", &mut saturate), Some(f64::MIN));
+	assert_eq!(deserialize_with::<f64>("-1e-400", &mut saturate).map(f64::to_bits), Some((-0f64).to_bits()));
+	assert_eq!(deserialize_with::<f32>("1e39", &mut saturate), Some(f32::MAX));
+	assert_eq!(deserialize_with::<f32>("1e-50", &mut saturate), Some(0.));
+	assert!(saturate.errors.is_empty());
+
+	let mut accept = Policy {float_range: FloatRange::Accept, ..Default::default()};
+	assert_eq!(deserialize_with::<f64>("1e400", &mut accept), Some(f64::INFINITY));
+	assert_eq!(deserialize_with::<f64>("-1e-400", &mut accept), Some(0.));
+	assert_eq!(deserialize_with::<f32>("-1e39", &mut accept), Some(f32::NEG_INFINITY));
+	assert_eq!(deserialize_with::<f32>("1e-50", &mut accept), Some(0.));
+	assert!(accept.errors.is_empty());
+}
+
+#[test]
+fn test_fixed_length() {
+	assert_eq!(crate::from_str::<[u8; 3]>("[1, 2, 3]").unwrap(), [1, 2, 3]);
+	assert_eq!(crate::from_str::<(u8, bool, String)>("[1, true, \"x\"]").unwrap(), (1, true, "x".to_owned()));
+	assert_eq!(crate::from_str::<[u8; 0]>("[]").unwrap(), []);
+
+	let errors = crate::from_str::<STDHashMap<String, [u8; 3]>>("{\"ok\": [1, 2, 3], \"short\": [1, 2]}")
+		.unwrap_err().into_errors();
+	assert_eq!(errors.len(), 1);
+	assert_eq!(errors[0].to_string(), "array is too short, expected 3 elements, found 2 at .short");
+
+	let errors = crate::from_str::<STDVec<(u8, bool)>>("[[1, true], [2, false, null, 4]]")
+		.unwrap_err().into_errors();
+	assert_eq!(errors.len(), 1);
+	assert_eq!(errors[0].to_string(), "array is too long, expected 2 elements, found 4 at [1]");
+
+	let errors = crate::from_str::<[(u8,); 2]>("[[1], []]").unwrap_err().into_errors();
+	assert_eq!(errors.len(), 1);
+	assert_eq!(errors[0].path, [KeyKind::Array(1)]);
+}
+
+#[test]
+fn test_duplicates() {
+	use crate::map::Map;
+	use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+	const SET: &str = "[3, 1, 3, 2, 1]";
+	const MAP: &str = "{\"b\": 1, \"a\": 2, \"b\": 3}";
+	let mut allow = Policy::default();
+	assert_eq!(deserialize_with::<HashSet<u8>>(SET, &mut allow), Some(HashSet::from([1, 2, 3])));
+	assert_eq!(deserialize_with::<BTreeSet<u8>>(SET, &mut allow), Some(BTreeSet::from([1, 2, 3])));
+	assert_eq!(deserialize_with::<STDHashMap<String, u8>>(MAP, &mut allow),
+		Some(STDHashMap::from([("a".to_owned(), 2), ("b".to_owned(), 3)])));
+	assert_eq!(deserialize_with::<BTreeMap<String, u8>>(MAP, &mut allow),
+		Some(BTreeMap::from([("a".to_owned(), 2), ("b".to_owned(), 3)])));
+	let map = deserialize_with::<Map<String, u8>>(MAP, &mut allow).unwrap();
+	assert_eq!(map.iter().map(|(key, value)| (key.as_str(), *value)).collect::<STDVec<_>>(), [("b", 3), ("a", 2)]);
+	assert!(allow.errors.is_empty());
+
+	let mut report = Policy {duplicates: Duplicates::Report, ..Default::default()};
+	assert_eq!(deserialize_with::<HashSet<u8>>(SET, &mut report), Some(HashSet::from([1, 2, 3])));
+	assert_eq!(deserialize_with::<BTreeSet<u8>>("[1, 2]", &mut report), Some(BTreeSet::from([1, 2])));
+	assert_eq!(report.errors, ["duplicate element in set", "duplicate element in set"]);
+	report.errors.clear();
+	assert_eq!(deserialize_with::<STDHashMap<String, u8>>(MAP, &mut report).map(|map| map["b"]), Some(3));
+	assert_eq!(deserialize_with::<BTreeMap<String, u8>>(MAP, &mut report).map(|map| map["b"]), Some(3));
+	assert_eq!(deserialize_with::<Map<String, u8>>(MAP, &mut report).map(|map| map["b"]), Some(3));
+	assert_eq!(deserialize_with::<Map<String, u8>>("{\"a\": 1}", &mut report).map(|map| map.len()), Some(1));
+	assert_eq!(report.errors, ["duplicate key in object"; 3]);
+}
+
+#[test]
+fn test_wrappers() {
+	use std::{cell::RefCell, rc::Rc, sync::{Arc, Mutex}};
 
-	test_deserialize_complex_3<Complex>(
-		"{\"what_if\":[\"much\\nwow\",578924],\"we_put\":[\"this is data\",7,\"big data\"],\"our\":null}",
-		Complex {
-			what_if: ComplexA::ThisIsAlsoAVariant(Cow::Owned("much\nwow".into()), 578924),
-			we_put: ComplexB("this is data".into(), 7, "big data".into()),
-			our: ComplexC
+	let arc = crate::from_str::<Arc<STDVec<u8>>>("[1, 2]").unwrap();
+	assert_eq!(*arc, [1, 2]);
+	let rc = crate::from_str::<Rc<str>>("\"shared\\n\"").unwrap();
+	assert_eq!(&*rc, "shared\n");
+	let boxed = crate::from_str::<Box<[bool]>>("[true, false]").unwrap();
+	assert_eq!(&*boxed, [true, false]);
+	let cow = crate::from_str::<Cow<[u8]>>("[]").unwrap();
+	assert!(matches!(cow, Cow::Owned(ref vec) if vec.is_empty()));
+	let mutex = crate::from_str::<Mutex<Option<u8>>>("null").unwrap();
+	assert_eq!(*mutex.lock().unwrap(), None);
+	let cell = crate::from_str::<RefCell<String>>("\"x\"").unwrap();
+	cell.borrow_mut().push('y');
+	assert_eq!(*cell.borrow(), "xy");
+
+	let errors = crate::from_str::<Arc<[u8]>>("[1, -1, 2]").unwrap_err().into_errors();
+	assert_eq!(errors.len(), 1);
+	assert_eq!(errors[0].path, [KeyKind::Array(1)]);
+	let errors = crate::from_str::<Mutex<Rc<str>>>("1").unwrap_err().into_errors();
+	assert_eq!(errors[0].to_string(), "expected a string, found a number");
+}
+
+#[test]
+fn test_deserialize_key() {
+	use crate::deserialize::r#trait::DeserializeKey;
+	use std::collections::BTreeMap;
+
+	#[derive(Debug, DeserializeKey, Eq, Ord, PartialEq, PartialOrd)]
+	enum Side {Left, Right}
+
+	#[derive(Debug, Eq, Ord, PartialEq, PartialOrd)]
+	struct Id(u32);
+
+	impl<'s, E> DeserializeKey<'s, E> for Id
+			where E: ErrorContext<'s> {
+		fn deserialize_key(key: Cow<'s, str>, error_context: &mut E) -> Option<Self> {
+			let id = key.strip_prefix("id-").and_then(|id| id.parse().ok()).map(Self);
+			if id.is_none() {
+				error_context.report_key_unparsable("an id");
+			}
+			id
 		}
-	);
+	}
+
+	assert_eq!(crate::from_str::<BTreeMap<i16, u8>>("{\"-3\": 1, \"7\": 2}").unwrap(),
+		BTreeMap::from([(-3, 1), (7, 2)]));
+	assert_eq!(crate::from_str::<BTreeMap<bool, u8>>("{\"true\": 1}").unwrap(), BTreeMap::from([(true, 1)]));
+	assert_eq!(crate::from_str::<BTreeMap<char, u8>>("{\"é\": 1}").unwrap(), BTreeMap::from([('é', 1)]));
+	assert_eq!(crate::from_str::<BTreeMap<Side, u8>>("{\"Right\": 1, \"Left\": 2}").unwrap(),
+		BTreeMap::from([(Side::Left, 2), (Side::Right, 1)]));
+	assert_eq!(crate::from_str::<BTreeMap<Id, u8>>("{\"id-4\": 1}").unwrap(), BTreeMap::from([(Id(4), 1)]));
+
+	let errors = crate::from_str::<STDHashMap<u8, u8>>("{\"1\": 1, \"256\": 2, \"x\": 3}")
+		.unwrap_err().into_errors();
+	assert_eq!(errors.iter().map(ToString::to_string).collect::<STDVec<_>>(), [
+		"key cannot be parsed as an unsigned 8 bit integer at .256",
+		"key cannot be parsed as an unsigned 8 bit integer at .x"
+	]);
+	let errors = crate::from_str::<BTreeMap<Side, u8>>("{\"Up\": 1}").unwrap_err().into_errors();
+	assert_eq!(errors[0].to_string(), "unknown variant, expected one of [\"Left\", \"Right\"] at .Up");
+	let errors = crate::from_str::<BTreeMap<Id, u8>>("{\"4\": 1}").unwrap_err().into_errors();
+	assert_eq!(errors[0].path, [KeyKind::Object(Cow::Borrowed("4"))]);
+	let errors = crate::from_str::<BTreeMap<char, u8>>("{\"ab\": 1}").unwrap_err().into_errors();
+	assert_eq!(errors.len(), 1);
+}
+
+#[test]
+fn test_duration() {
+	use std::time::Duration;
 
-	test_deserialize_complex_5<Complex>(
-		"{\"what_if\":{\"beds\":\"i hate making\\n\\t\\rtest data\\b\",\"together\":\"it's finally over\",\"minecraft\":-4839855329580},\"we_put\":[\"this is data\",7,\"big data\"],\"our\":null}",
-		Complex {
-			what_if: ComplexA::IfYouHaveMoreCreativeNamesPleaseMakeAPullRequest {
-				minecraft: -4839855329580,
-				beds: "i hate making\n\t\rtest data\u{8}".into(),
-				together: "it's finally over"
-			},
-			we_put: ComplexB("this is data".into(), 7, "big data".into()),
-			our: ComplexC
+	let mut policy = Policy::default();
+	for (input, expected) in [
+		("{\"secs\": 90, \"nanos\": 5}", Duration::new(90, 5)),
+		("\"PT1H30M\"", Duration::from_secs(5_400)),
+		("\"P1DT0.5S\"", Duration::new(86_400, 500_000_000)),
+		("\"P2W1D\"", Duration::from_secs(15 * 86_400)),
+		("\"PT1M0,25S\"", Duration::from_millis(60_250)),
+		("\"PT0.0000000019S\"", Duration::from_nanos(1)),
+		("\"PT1.5M\"", Duration::from_secs(90))
+	] {
+		assert_eq!(deserialize_with::<Duration>(input, &mut policy), Some(expected), "{}", input);
+	}
+	assert!(policy.errors.is_empty());
+
+	for input in ["\"1H\"", "\"P\"", "\"PT\"", "\"P1Y\"", "\"PT1S1H\"", "\"PT1M1M\"", "\"P1DT1D\"",
+			"\"PT1.5M1S\"", "\"PT.5S\"", "\"PT1.S\"", "\"PT1\"", "\"PTT1S\"",
+			"\"PT340282366920938463463374607431.9S\"", "\"PT18446744073709551616S\"",
+			"{\"secs\": 1}", "{\"secs\": 18446744073709551615, \"nanos\": 1000000000}", "1"] {
+		let errors = policy.errors.len();
+		assert_eq!(deserialize_with::<Duration>(input, &mut policy), None, "{}", input);
+		assert_eq!(policy.errors.len(), errors + 1, "{}", input);
+	}
+	assert_eq!(policy.errors[4], "string cannot be parsed: components are out of order or repeated");
+	const OVERFLOW: &str = "duration is too large to be represented by target (a Duration)";
+	assert_eq!(policy.errors[12], OVERFLOW);
+	assert_eq!(policy.errors[13], OVERFLOW);
+	assert_eq!(policy.errors[15], OVERFLOW);
+}
+
+#[test]
+fn test_from_str_values() {
+	use crate::deserialize::r#trait::FromStrValue;
+	use std::{net::{IpAddr, Ipv4Addr, SocketAddr}, path::PathBuf};
+
+	assert_eq!(crate::from_str::<IpAddr>("\"::1\"").unwrap(), IpAddr::from([0, 0, 0, 0, 0, 0, 0, 1]));
+	assert_eq!(crate::from_str::<Ipv4Addr>("\"10.0.0.1\"").unwrap(), Ipv4Addr::new(10, 0, 0, 1));
+	assert_eq!(crate::from_str::<SocketAddr>("\"127.0.0.1:80\"").unwrap(), SocketAddr::from(([127, 0, 0, 1], 80)));
+	assert_eq!(crate::from_str::<PathBuf>("\"a/b\"").unwrap(), PathBuf::from("a/b"));
+	assert_eq!(crate::from_str::<FromStrValue<u8>>("\"42\"").unwrap().into_inner(), 42);
+	let map = crate::from_str::<STDHashMap<FromStrValue<Ipv4Addr>, bool>>("{\"1.2.3.4\": true}").unwrap();
+	assert_eq!(map.into_keys().map(FromStrValue::into_inner).collect::<STDVec<_>>(), [Ipv4Addr::new(1, 2, 3, 4)]);
+
+	let errors = crate::from_str::<STDVec<Ipv4Addr>>("[\"1.2.3.4\", \"1.2.3\", 7]").unwrap_err().into_errors();
+	assert_eq!(errors.iter().map(ToString::to_string).collect::<STDVec<_>>(), [
+		"string cannot be parsed: invalid IPv4 address syntax at [1]",
+		"expected a string, found a number at [2]"
+	]);
+	let errors = crate::from_str::<FromStrValue<u8>>("\"256\"").unwrap_err().into_errors();
+	assert_eq!(errors[0].to_string(), "string cannot be parsed: number too large to fit in target type");
+	let errors = crate::from_str::<STDHashMap<FromStrValue<u8>, u8>>("{\"x\": 1}").unwrap_err().into_errors();
+	assert_eq!(errors[0].path, [KeyKind::Object(Cow::Borrowed("x"))]);
+}
+
+#[test]
+fn test_timestamp() {
+	use crate::timestamp::{EpochMillis, EpochSeconds, Timestamp, TimestampError};
+	use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+	let parse = |string: &str| string.parse::<Timestamp>().map(|time| (time.unix_seconds(), time.subsec_nanos()));
+	assert_eq!(parse("1985-04-12T23:20:50.52Z"), Ok((482_196_050, 520_000_000)));
+	assert_eq!(parse("1996-12-19T16:39:57-08:00"), Ok((851_042_397, 0)));
+	assert_eq!(parse("2000-01-01T05:30:00+05:30"), Ok((946_684_800, 0)));
+	assert_eq!(parse("2000-01-01 00:00:00.000000001z"), Ok((946_684_800, 1)));
+	assert_eq!(parse("1969-12-31T23:59:59.9999999999Z"), Ok((-1, 999_999_999)));
+	assert_eq!(parse("1990-12-31T23:59:60Z"), parse("1991-01-01T00:00:00Z"));
+	assert_eq!(parse("1990-12-31T15:59:60-08:00"), parse("1991-01-01T00:00:00Z"));
+	assert_eq!(parse("2000-02-29T00:00:00Z"), Ok((951_782_400, 0)));
+
+	assert_eq!(parse("2001-02-29T00:00:00Z"), Err(TimestampError::DayOutOfRange {year: 2001, month: 2, day: 29}));
+	assert_eq!(parse("1900-02-29T00:00:00Z"), Err(TimestampError::DayOutOfRange {year: 1900, month: 2, day: 29}));
+	assert_eq!(parse("2021-04-31T00:00:00Z"), Err(TimestampError::DayOutOfRange {year: 2021, month: 4, day: 31}));
+	assert_eq!(parse("2021-13-01T00:00:00Z"), Err(TimestampError::MonthOutOfRange(13)));
+	assert_eq!(parse("2021-01-01T00:00:61Z"), Err(TimestampError::SecondOutOfRange(61)));
+	assert_eq!(parse("2021-01-01T12:30:60Z"), Err(TimestampError::SecondOutOfRange(60)));
+	assert_eq!(parse("1990-12-31T23:59:60+01:00"), Err(TimestampError::SecondOutOfRange(60)));
+	assert_eq!(parse("2021-01-01T00:00:00+24:00"), Err(TimestampError::OffsetOutOfRange));
+	assert_eq!(parse("2021-01-01T00:00:00"), Err(TimestampError::Unexpected {index: 19, expected: "'Z' or an offset"}));
+	assert_eq!(parse("2021-01-01T00:00:00.Z"), Err(TimestampError::Unexpected {index: 20, expected: "a fractional digit"}));
+	assert_eq!(parse("9999-12-31T23:59:59-00:01"), Err(TimestampError::OutOfRange));
+	assert_eq!(parse("0000-01-01T00:00:00+00:01"), Err(TimestampError::OutOfRange));
+	assert_eq!(parse("0000-01-01T00:00:00Z").map(|(seconds, _)| seconds), Ok(-62_167_219_200));
+	assert_eq!(Timestamp::from_unix(i64::MAX, 0), None);
+	assert_eq!(Timestamp::from_unix(0, 1_000_000_000), None);
+
+	for string in ["1985-04-12T23:20:50.52Z", "0000-01-01T00:00:00Z", "9999-12-31T23:59:59.999999999Z",
+			"1969-07-20T20:17:40.000000001Z", "2024-02-29T12:00:00Z"] {
+		assert_eq!(string.parse::<Timestamp>().unwrap().to_string(), string);
+	}
+	assert_eq!("1996-12-19T16:39:57-08:00".parse::<Timestamp>().unwrap().to_string(), "1996-12-20T00:39:57Z");
+
+	let seconds = crate::from_str::<EpochSeconds>("1.5").unwrap();
+	assert_eq!(seconds.0.to_string(), "1970-01-01T00:00:01.5Z");
+	assert_eq!(crate::to_string(&seconds).unwrap(), "1.5");
+	let millis = crate::from_str::<EpochMillis>("-1250").unwrap();
+	assert_eq!((millis.0.unix_seconds(), millis.0.subsec_nanos(), millis.0.unix_millis()), (-2, 750_000_000, -1250));
+	assert_eq!(crate::to_string(&millis).unwrap(), "-1250");
+	assert_eq!(crate::to_string(&EpochSeconds(millis.0)).unwrap(), "-1.25");
+	let millis = crate::from_str::<EpochMillis>("\"2001-09-09T01:46:40Z\"").unwrap();
+	assert_eq!(crate::to_string(&millis).unwrap(), "1000000000000");
+	assert_eq!(crate::to_string(&millis.0).unwrap(), "\"2001-09-09T01:46:40Z\"");
+	for input in ["1e20", "-253402300800", "true"] {
+		assert_eq!(crate::from_str::<EpochSeconds>(input).unwrap_err().into_errors().len(), 1, "{}", input);
+	}
+	let errors = crate::from_str::<Timestamp>("\"2001-02-29T00:00:00Z\"").unwrap_err().into_errors();
+	assert_eq!(errors[0].to_string(), "invalid timestamp: day 29 is out of range for 2001-02");
+
+	for time in [UNIX_EPOCH, UNIX_EPOCH + Duration::new(1_700_000_000, 123), UNIX_EPOCH - Duration::from_millis(1_250)] {
+		let timestamp = Timestamp::try_from(time).unwrap();
+		assert_eq!(SystemTime::try_from(timestamp).unwrap(), time);
+	}
+	let before = Timestamp::try_from(UNIX_EPOCH - Duration::from_millis(1_250)).unwrap();
+	assert_eq!(before.to_string(), "1969-12-31T23:59:58.75Z");
+	assert_eq!(Timestamp::try_from(UNIX_EPOCH + Duration::from_secs(300_000_000_000)), Err(TimestampError::OutOfRange));
+}
+
+#[test]
+fn test_deserialize_in_place() {
+	#[derive(Debug, Default, Deserialize, PartialEq)]
+	struct Record {
+		name: String,
+		values: STDVec<String>,
+		table: STDHashMap<String, STDVec<u8>>
+	}
+
+	let mut policy = Policy::default();
+	let mut string = String::with_capacity(64);
+	let buffer = string.as_ptr();
+	assert!(deserialize_in_place_with(&mut string, "\"first\"", &mut policy));
+	assert!(deserialize_in_place_with(&mut string, "\"second\\n\"", &mut policy));
+	assert_eq!((string.as_str(), string.as_ptr()), ("second\n", buffer));
+
+	let mut vec: STDVec<String> = STDVec::new();
+	assert!(deserialize_in_place_with(&mut vec, "[\"a long first string\", \"b\", \"c\"]", &mut policy));
+	let buffers: STDVec<_> = vec.iter().map(|string| string.as_ptr()).collect();
+	assert!(deserialize_in_place_with(&mut vec, "[\"x\", \"y\"]", &mut policy));
+	assert_eq!(vec, ["x", "y"]);
+	assert_eq!(vec.iter().map(|string| string.as_ptr()).collect::<STDVec<_>>(), buffers[..2]);
+	assert!(deserialize_in_place_with(&mut vec, "[\"x\", \"y\", \"z\", \"w\"]", &mut policy));
+	assert_eq!(vec, ["x", "y", "z", "w"]);
+	assert!(deserialize_in_place_with(&mut vec, "[]", &mut policy));
+	assert!(vec.is_empty());
+
+	let mut map: STDHashMap<String, STDVec<u8>> = STDHashMap::with_capacity(32);
+	assert!(deserialize_in_place_with(&mut map, "{\"kept\": [1, 2, 3], \"stale\": [4]}", &mut policy));
+	let (capacity, key, buffer) = (map.capacity(), map.get_key_value("kept").unwrap().0.as_ptr(), map["kept"].as_ptr());
+	assert!(deserialize_in_place_with(&mut map, "{\"new\": [], \"kept\": [5]}", &mut policy));
+	assert_eq!(map, STDHashMap::from([("kept".to_owned(), vec![5]), ("new".to_owned(), vec![])]));
+	assert_eq!((map.capacity(), map.get_key_value("kept").unwrap().0.as_ptr(), map["kept"].as_ptr()),
+		(capacity, key, buffer));
+	assert!(policy.errors.is_empty());
+
+	let mut record = Record::default();
+	assert!(deserialize_in_place_with(&mut record,
+		"{\"name\": \"a name\", \"values\": [\"1\", \"2\", \"3\"], \"table\": {\"k\": [1, 2]}}", &mut policy));
+	let buffers = (record.name.as_ptr(), record.values.as_ptr(), record.values[0].as_ptr(), record.table["k"].as_ptr());
+	assert!(deserialize_in_place_with(&mut record,
+		"{\"table\": {\"k\": [3]}, \"values\": [\"4\"], \"name\": \"b\"}", &mut policy));
+	assert_eq!(record, Record {
+		name: "b".to_owned(),
+		values: vec!["4".to_owned()],
+		table: STDHashMap::from([("k".to_owned(), vec![3])])
+	});
+	assert_eq!((record.name.as_ptr(), record.values.as_ptr(), record.values[0].as_ptr(), record.table["k"].as_ptr()), buffers);
+	assert!(policy.errors.is_empty());
+
+	assert!(!deserialize_in_place_with(&mut record, "{\"name\": \"c\"}", &mut policy));
+	assert_eq!(policy.errors, ["missing fields"]);
+}
+
+#[test]
+fn test_deserialize_seed() {
+	use crate::deserialize::{
+		error::{Errors, SyntaxError},
+		from_str_seed_default,
+		r#trait::{DeserializeSeed, MapSeed, VecSeed}
+	};
+	use std::{cell::RefCell, marker::PhantomData};
+
+	/// Seed interning strings, deserializing each to its index.
+	#[derive(Default)]
+	struct Interner(RefCell<STDVec<String>>);
+
+	impl<'s, E> DeserializeSeed<'s, E> for &Interner
+			where E: ErrorContext<'s> {
+		type Value = usize;
+
+		fn deserialize<'d>(self, value: ValueDeserializer<'d, 's>, error_context: &mut E)
+				-> Result<Option<usize>, SyntaxError> {
+			let Some(string) = String::deserialize(value, error_context)? else {return Ok(None)};
+			let mut strings = self.0.borrow_mut();
+			Ok(Some(strings.iter().position(|interned| *interned == string).unwrap_or_else(|| {
+				strings.push(string);
+				strings.len() - 1
+			})))
 		}
+	}
+
+	let interner = Interner::default();
+	let (result, errors) = from_str_seed_default::<_, Errors>(MapSeed::<String, _>::new(VecSeed(&interner)),
+		"{\"a\": [\"x\", \"y\", \"x\"], \"b\": [\"y\", \"z\"]}");
+	assert!(errors.is_empty());
+	assert_eq!(result.unwrap().unwrap(), STDHashMap::from([("a".to_owned(), vec![0, 1, 0]), ("b".to_owned(), vec![1, 2])]));
+	assert_eq!(*interner.0.borrow(), ["x", "y", "z"]);
+
+	let (result, errors) = from_str_seed_default::<_, Errors>(VecSeed(&interner), "[\"z\", 1, \"w\"]");
+	assert_eq!(result.unwrap().unwrap(), [2, 3]);
+	assert_eq!(errors.into_errors()[0].path, [KeyKind::Array(1)]);
+
+	let (result, errors) = from_str_seed_default::<_, Errors>(PhantomData::<STDVec<u8>>, "[1, 2]");
+	assert!(errors.is_empty());
+	assert_eq!(result.unwrap().unwrap(), [1, 2]);
+
+	let (result, _) = from_str_seed_default::<_, Errors>(VecSeed(&interner), "[] []");
+	assert_eq!(result.unwrap().unwrap(), []);
+}
+
+#[test]
+fn test_element_failure() {
+	use crate::{deserialize::r#trait::{DeserializeElements, ElementFailure}, map::Map};
+	use std::collections::{BTreeMap, VecDeque};
+
+	fn elements<'s, T>(input: &'s str, on_failure: ElementFailure<T::Element>) -> (Option<T>, usize)
+			where T: DeserializeElements<'s, Policy> {
+		let mut policy = Policy::default();
+		let mut deserializer = Deserializer::new(input);
+		let value = ValueDeserializer::new(&mut deserializer)
+			.and_then(|value| T::deserialize_elements(value, &mut policy, on_failure))
+			.unwrap();
+		(value, policy.errors.len())
+	}
+
+	fn elements_in_place<'s, T>(place: &mut T, input: &'s str, on_failure: ElementFailure<T::Element>) -> bool
+			where T: DeserializeElements<'s, Policy> {
+		let mut deserializer = Deserializer::new(input);
+		ValueDeserializer::new(&mut deserializer)
+			.and_then(|value| place.deserialize_elements_in_place(value, &mut Policy::default(), on_failure))
+			.unwrap()
+	}
+
+	const ARRAY: &str = "[1, \"x\", 3, null]";
+	assert_eq!(elements::<STDVec<u8>>(ARRAY, ElementFailure::Drop), (Some(vec![1, 3]), 2));
+	assert_eq!(elements::<STDVec<u8>>(ARRAY, ElementFailure::Substitute(|| 0)), (Some(vec![1, 0, 3, 0]), 2));
+	assert_eq!(elements::<STDVec<u8>>(ARRAY, ElementFailure::Fail), (None, 2));
+	assert_eq!(elements::<VecDeque<u8>>(ARRAY, ElementFailure::Substitute(u8::default)).0, Some(VecDeque::from([1, 0, 3, 0])));
+
+	const OBJECT: &str = "{\"a\": 1, \"b\": \"x\", \"c\": 3}";
+	assert_eq!(elements::<BTreeMap<String, u8>>(OBJECT, ElementFailure::Drop).0.map(|map| map.into_keys().collect::<String>()),
+		Some("ac".to_owned()));
+	assert_eq!(elements::<STDHashMap<String, u8>>(OBJECT, ElementFailure::Substitute(|| 9)).0.map(|map| map["b"]), Some(9));
+	assert_eq!(elements::<Map<String, u8>>(OBJECT, ElementFailure::Fail), (None, 1));
+
+	let mut vec = vec![7, 7, 7, 7, 7];
+	assert!(elements_in_place(&mut vec, ARRAY, ElementFailure::Drop));
+	assert_eq!(vec, [1, 3]);
+	assert!(elements_in_place(&mut vec, ARRAY, ElementFailure::Substitute(|| 0)));
+	assert_eq!(vec, [1, 0, 3, 0]);
+	assert!(!elements_in_place(&mut vec, ARRAY, ElementFailure::Fail));
+	assert!(vec.deserialize_in_place(ValueDeserializer::new(&mut Deserializer::new(ARRAY)).unwrap(), &mut ()).unwrap());
+	assert_eq!(vec, [1, 3]);
+
+	let mut map = STDHashMap::from([("b".to_owned(), 5), ("z".to_owned(), 5)]);
+	assert!(elements_in_place(&mut map, OBJECT, ElementFailure::Drop));
+	assert_eq!(map, STDHashMap::from([("a".to_owned(), 1), ("c".to_owned(), 3)]));
+	assert!(elements_in_place(&mut map, OBJECT, ElementFailure::Substitute(|| 9)));
+	assert_eq!(map, STDHashMap::from([("a".to_owned(), 1), ("b".to_owned(), 9), ("c".to_owned(), 3)]));
+	assert!(!elements_in_place(&mut map, OBJECT, ElementFailure::Fail));
+
+	#[derive(Debug, Default, Deserialize, PartialEq)]
+	struct Lists {
+		#[qj(element_failure = "drop")]
+		dropped: STDVec<u8>,
+		#[qj(element_failure = "default")]
+		defaulted: STDHashMap<String, u8>,
+		#[qj(element_failure = "fail")]
+		strict: STDVec<u8>
+	}
+
+	let mut policy = Policy::default();
+	let input = "{\"dropped\": [1, -1, 2], \"defaulted\": {\"k\": true}, \"strict\": [3]}";
+	let expected = Lists {
+		dropped: vec![1, 2],
+		defaulted: STDHashMap::from([("k".to_owned(), 0)]),
+		strict: vec![3]
+	};
+	assert_eq!(deserialize_with::<Lists>(input, &mut policy).as_ref(), Some(&expected));
+	let mut lists = Lists {dropped: vec![9; 8], ..Default::default()};
+	assert!(deserialize_in_place_with(&mut lists, input, &mut policy));
+	assert_eq!(lists, expected);
+	assert_eq!(policy.errors.len(), 4);
+
+	let input = "{\"dropped\": [], \"defaulted\": {}, \"strict\": [3, \"4\"]}";
+	assert_eq!(deserialize_with::<Lists>(input, &mut policy), None);
+	assert!(!deserialize_in_place_with(&mut lists, input, &mut policy));
+	assert_eq!(policy.errors.len(), 6);
+}
+
+#[test]
+fn test_serialize_compact() {
+	let value = (vec![Some(1.5), None], "a\"\n\u{1}", STDHashMap::from([(7u8, true)]));
+	assert_eq!(crate::to_string(&value).unwrap(),
+		"[[1.5,null],\"a\\\"\\n\\u0001\",{\"7\":true}]");
+}
+
+#[test]
+fn test_serialize_non_finite() {
+	assert!(crate::to_string(&f64::NAN).is_err());
+	assert!(crate::to_string(&vec![1.0, f64::INFINITY]).is_err());
+	assert!(crate::to_string(&f32::NEG_INFINITY).is_err());
+
+	// Nothing is written for the rejected value, not even `null`.
+	let mut output = String::new();
+	assert!(crate::serialize::to_writer(&mut output, &Some(f64::NAN)).is_err());
+	assert_eq!(output, "");
+}
+
+#[test]
+fn test_serialize_round_trip() {
+	let value: (STDVec<f64>, String, STDHashMap<String, u64>) =
+		(vec![0.1, -0.0, 1e300, 5e-324], "tab\there".into(), STDHashMap::from([("k".into(), u64::MAX)]));
+	let json = crate::to_string(&value).unwrap();
+	assert_eq!(crate::from_str::<(STDVec<f64>, String, STDHashMap<String, u64>)>(&json).unwrap(), value);
+}
+
+#[test]
+fn test_derive_round_trip() {
+	use crate::{deserialize::error::Errors, serialize::r#trait::Serialize};
+	use std::fmt::Debug;
+
+	#[derive(Debug, Deserialize, PartialEq, Serialize)]
+	struct Named {
+		id: u32,
+		tags: STDVec<String>
+	}
+
+	#[derive(Debug, Deserialize, PartialEq, Serialize)]
+	struct Tuple(bool, String);
+
+	#[derive(Debug, Deserialize, PartialEq, Serialize)]
+	struct Newtype(i64);
+
+	#[derive(Debug, Deserialize, PartialEq, Serialize)]
+	struct Unit;
+
+	#[derive(Debug, Deserialize, PartialEq, Serialize)]
+	enum Shape {
+		Empty,
+		Circle(f64),
+		Line(i32, i32),
+		Rectangle {width: u8, height: u8}
+	}
+
+	fn round_trip<T>(value: T, expected: &str)
+			where T: Debug + PartialEq + Serialize + for<'s> Deserialize<'s, Errors<'s>> {
+		let json = crate::to_string(&value).unwrap();
+		assert_eq!(json, expected);
+		assert_eq!(crate::from_str::<T>(&json).unwrap(), value);
+	}
+
+	round_trip(Named {id: 7, tags: vec!["a".into(), "b".into()]}, "{\"id\":7,\"tags\":[\"a\",\"b\"]}");
+	round_trip(Tuple(true, "x".into()), "[true,\"x\"]");
+	round_trip(Newtype(-3), "-3");
+	round_trip(Unit, "null");
+	round_trip(Shape::Empty, "\"Empty\"");
+	round_trip(Shape::Circle(0.5), "{\"Circle\":0.5}");
+	round_trip(Shape::Line(1, -1), "{\"Line\":[1,-1]}");
+	round_trip(Shape::Rectangle {width: 2, height: 3}, "{\"Rectangle\":{\"width\":2,\"height\":3}}");
+}
+
+#[test]
+fn test_serialize_pretty() {
+	use crate::serialize::{format::{Indent, Newline, PrettyConfig}, to_string_pretty};
+
+	let value = (vec![1, 2], STDVec::<u8>::new(), STDHashMap::from([("a", ())]));
+	assert_eq!(to_string_pretty(&value, PrettyConfig::default()).unwrap(),
+		"[\n\t[\n\t\t1,\n\t\t2\n\t],\n\t[],\n\t{\n\t\t\"a\": null\n\t}\n]");
+
+	let config = PrettyConfig {
+		indent: Indent::Spaces(2),
+		newline: Newline::CrLf,
+		space_after_colon: false,
+		compact_empty: false,
+		..Default::default()
+	};
+	assert_eq!(to_string_pretty(&value, config).unwrap(),
+		"[\r\n  [\r\n    1,\r\n    2\r\n  ],\r\n  [\r\n  ],\r\n  {\r\n    \"a\":null\r\n  }\r\n]");
+}
+
+#[test]
+fn test_reformat() {
+	use crate::serialize::{format::{Compact, Pretty}, reformat};
+
+	const INPUT: &str = " { \"a\" : [ 1.50e3 , \"\\u00e9\" ] , \"b\" : { } } ";
+	assert_eq!(reformat(INPUT, Compact).unwrap(), "{\"a\":[1.50e3,\"é\"],\"b\":{}}");
+	assert_eq!(reformat(INPUT, Pretty::default()).unwrap(),
+		"{\n\t\"a\": [\n\t\t1.50e3,\n\t\t\"é\"\n\t],\n\t\"b\": {}\n}");
+	assert!(reformat("[1] 2", Compact).is_err());
+}
+
+#[test]
+fn test_reformat_smart() {
+	use crate::serialize::{format::Indent, smart::{reformat_smart, SmartConfig}};
+
+	const INPUT: &str = "{\"name\":\"square\",\"points\":[[0,0],[0,1],[1,1],[1,0]],\"closed\":true}";
+	assert_eq!(reformat_smart(INPUT, &SmartConfig::default()).unwrap(),
+		"{ \"name\": \"square\", \"points\": [[0, 0], [0, 1], [1, 1], [1, 0]], \"closed\": true }");
+
+	let config = SmartConfig {width: 30, indent: Indent::Spaces(2), align_values: true, ..Default::default()};
+	assert_eq!(reformat_smart(INPUT, &config).unwrap(),
+		"{\n  \"name\":   \"square\",\n  \"points\": [\n    [0, 0],\n    [0, 1],\n    [1, 1],\n    [1, 0]\n  ],\n  \"closed\": true\n}");
+}
+
+#[test]
+fn test_to_string_smart() {
+	use crate::serialize::smart::{reformat_smart, to_string_smart, SmartConfig};
+
+	let value = (STDHashMap::from([("b", vec![1, 2]), ("a", vec![]), ("c", vec![3])]), "é");
+	let config = SmartConfig {sort_keys: true, ..Default::default()};
+	assert_eq!(to_string_smart(&value, &config).unwrap(),
+		"[{ \"a\": [], \"b\": [1, 2], \"c\": [3] }, \"é\"]");
+
+	let config = SmartConfig {width: 16, ..config};
+	assert_eq!(to_string_smart(&value, &config).unwrap(),
+		"[\n\t{\n\t\t\"a\": [],\n\t\t\"b\": [\n\t\t\t1,\n\t\t\t2\n\t\t],\n\t\t\"c\": [3]\n\t},\n\t\"é\"\n]");
+
+	let input = "{\"z\":{\"y\":1,\"x\":2},\"a\":0}";
+	assert_eq!(reformat_smart(input, &SmartConfig::default()).unwrap(), "{ \"z\": { \"y\": 1, \"x\": 2 }, \"a\": 0 }");
+	assert_eq!(reformat_smart(input, &SmartConfig {sort_keys: true, ..Default::default()}).unwrap(),
+		"{ \"a\": 0, \"z\": { \"x\": 2, \"y\": 1 } }");
+	assert!(to_string_smart(&[f64::NAN], &SmartConfig::default()).is_err());
+}
+
+#[test]
+fn test_canonicalize() {
+	use crate::serialize::canonical::{canonicalize, to_string_canonical};
+
+	// Examples from RFC 8785.
+	assert_eq!(canonicalize("{\"\\u20ac\": 1, \"\\r\": 2, \"\\ud83d\\ude00\": 3, \"1\": 4, \"\\u00f6\": 5, \"\\ufb33\": 6, \"\\u0080\": 7}").unwrap(),
+		"{\"\\r\":2,\"1\":4,\"\u{80}\":7,\"\u{f6}\":5,\"\u{20ac}\":1,\"\u{1f600}\":3,\"\u{fb33}\":6}");
+	assert_eq!(canonicalize("[333333333.33333329, 1E30, 4.50, 2e-3, 0.000000000000000000000000001, -0, 1e21, 1e20, 1e-7, 1e-6]").unwrap(),
+		"[333333333.3333333,1e+30,4.5,0.002,1e-27,0,1e+21,100000000000000000000,1e-7,0.000001]");
+	assert_eq!(canonicalize("{\"a\": \"\\u000f\\/\\n\", \"b\": [true, null]}").unwrap(),
+		"{\"a\":\"\\u000f/\\n\",\"b\":[true,null]}");
+	assert!(canonicalize("1e400").is_err());
+	assert!(canonicalize("{} {}").is_err());
+
+	let value = maplit::btreemap! {"b" => 1.5f64, "a" => 100f64};
+	assert_eq!(to_string_canonical(&value).unwrap(), "{\"a\":100,\"b\":1.5}");
+
+	// Serializing canonically agrees with rewriting the compact output.
+	let value = (
+		vec![STDHashMap::from([("\u{20ac}", vec![-0.0, 1e21]), ("\r", vec![]), ("1", vec![2e-7])])],
+		STDHashMap::from([("z", STDHashMap::from([("y", u64::MAX), ("x", 0)])), ("a", STDHashMap::new())]),
+		"\u{1f600}\n"
 	);
+	assert_eq!(to_string_canonical(&value).unwrap(), canonicalize(&crate::to_string(&value).unwrap()).unwrap());
+	assert_eq!(to_string_canonical(&value).unwrap(),
+		"[[{\"\\r\":[],\"1\":[2e-7],\"\u{20ac}\":[0,1e+21]}],{\"a\":{},\"z\":{\"x\":0,\"y\":18446744073709552000}},\"\u{1f600}\\n\"]");
+}
+
+#[test]
+fn test_json_writer() {
+	use crate::serialize::{format::{Indent, Pretty, PrettyConfig}, writer::{JsonWriter, WriterError}};
+
+	let mut writer = JsonWriter::new(STDVec::new());
+	writer.begin_object().unwrap();
+	writer.key("rows").unwrap();
+	writer.begin_array().unwrap();
+	for row in 0..3u32 {
+		writer.value(&(row, format!("row {}", row))).unwrap();
+	}
+	assert!(matches!(writer.key("oops"), Err(WriterError::UnexpectedKey)));
+	assert!(matches!(writer.end_object(), Err(WriterError::UnexpectedEnd)));
+	writer.end_array().unwrap();
+	assert!(matches!(writer.value(&1), Err(WriterError::ExpectedKey)));
+	writer.key("empty").unwrap();
+	writer.begin_object().unwrap();
+	writer.end_object().unwrap();
+	writer.end_object().unwrap();
+	assert!(matches!(writer.value(&1), Err(WriterError::TrailingValue)));
+	assert_eq!(String::from_utf8(writer.finish().unwrap()).unwrap(),
+		"{\"rows\":[[0,\"row 0\"],[1,\"row 1\"],[2,\"row 2\"]],\"empty\":{}}");
+
+	let mut writer = JsonWriter::with_formatter(STDVec::new(),
+		Pretty::new(PrettyConfig {indent: Indent::Spaces(2), ..Default::default()}));
+	writer.begin_array().unwrap();
+	writer.value(&[1, 2]).unwrap();
+	writer.value(&true).unwrap();
+	assert!(matches!(writer.finish(), Err(WriterError::Incomplete)));
+
+	let mut writer = JsonWriter::with_formatter(STDVec::new(),
+		Pretty::new(PrettyConfig {indent: Indent::Spaces(2), ..Default::default()}));
+	writer.begin_array().unwrap();
+	writer.value(&[1, 2]).unwrap();
+	writer.value(&true).unwrap();
+	writer.end_array().unwrap();
+	assert_eq!(String::from_utf8(writer.finish().unwrap()).unwrap(),
+		"[\n  [\n    1,\n    2\n  ],\n  true\n]");
+}
+
+#[test]
+fn test_escape() {
+	use crate::serialize::{
+		canonical::canonicalize_with,
+		format::{Compact, Escape, Escaped, PrettyConfig},
+		smart::{reformat_smart, SmartConfig},
+		to_string_pretty, to_string_with
+	};
+
+	let value = STDHashMap::from([("</script>", "a & b\u{2028}é😀")]);
+	assert_eq!(to_string_with(&value, Compact).unwrap(),
+		"{\"</script>\":\"a & b\u{2028}é😀\"}");
+	assert_eq!(to_string_with(&value, Escaped::new(Compact, Escape::Html)).unwrap(),
+		"{\"\\u003c/script\\u003e\":\"a \\u0026 b\\u2028é😀\"}");
+	assert_eq!(to_string_with(&value, Escaped::new(Compact, Escape::Ascii)).unwrap(),
+		"{\"</script>\":\"a & b\\u2028\\u00e9\\ud83d\\ude00\"}");
+	assert_eq!(to_string_pretty(&value, PrettyConfig {escape: Escape::Html, ..Default::default()}).unwrap(),
+		"{\n\t\"\\u003c/script\\u003e\": \"a \\u0026 b\\u2028é😀\"\n}");
+
+	const INPUT: &str = "{\"b\": \"ü\", \"a\": \"<\"}";
+	assert_eq!(canonicalize_with(INPUT, Escape::Ascii).unwrap(), "{\"a\":\"<\",\"b\":\"\\u00fc\"}");
+	assert_eq!(reformat_smart(INPUT, &SmartConfig {escape: Escape::Html, ..Default::default()}).unwrap(),
+		"{ \"b\": \"ü\", \"a\": \"\\u003c\" }");
+}
+
+#[test]
+fn test_value() {
+	use crate::{value::Value, deserialize::error::JSONType};
+
+	const INPUT: &str = "{\"name\": \"qj\", \"tags\": [\"json\", 1.5, -3], \"meta\": {\"ok\": true, \"none\": null}}";
+	let value: Value = crate::from_str(INPUT).unwrap();
+	assert_eq!(value.kind(), JSONType::Object);
+	assert!(matches!(value["name"], Value::String(Cow::Borrowed("qj"))));
+	assert_eq!(value["tags"][0].as_str(), Some("json"));
+	assert_eq!(value["tags"][1].as_f64(), Some(1.5));
+	assert_eq!(value["tags"][1].as_i64(), None);
+	assert_eq!(value["tags"][2].as_i64(), Some(-3));
+	assert_eq!(value["meta"]["ok"].as_bool(), Some(true));
+	assert!(value["meta"]["none"].is_null());
+	assert!(value["missing"][7]["deeper"].is_null());
+	assert_eq!(value.get("tags").and_then(Value::as_array).map(<[_]>::len), Some(3));
+
+	let mut owned: Value<'static> = value.clone().into_owned();
+	assert_eq!(owned, value);
+	owned["meta"]["added"] = Value::from("yes");
+	owned["tags"][2] = Value::Null;
+	assert_eq!(owned["meta"]["added"].as_str(), Some("yes"));
+	assert!(owned["tags"][2].is_null());
+
+	let json = crate::to_string(&owned).unwrap();
+	let reparsed: Value = crate::from_str(&json).unwrap();
+	assert_eq!(reparsed, owned);
+}
+
+#[test]
+fn test_derive_borrowed() {
+	use crate::{serialize::r#trait::Serialize, value::Value};
+
+	#[derive(Debug, Deserialize, PartialEq, Serialize)]
+	struct S<'a> {
+		v: Value<'a>,
+		s: Cow<'a, str>
+	}
+
+	#[derive(Serialize)]
+	struct Pair<T>(T, T);
+
+	const INPUT: &str = "{\"v\":[true,\"x\"],\"s\":\"plain\"}";
+	let borrowed: S = crate::from_str(INPUT).unwrap();
+	assert!(matches!(borrowed.s, Cow::Borrowed("plain")));
+	assert_eq!(crate::to_string(&borrowed).unwrap(), INPUT);
+	assert_eq!(crate::to_string(&Pair(S {v: Value::Null, s: "".into()}, borrowed)).unwrap(),
+		"[{\"v\":null,\"s\":\"\"},{\"v\":[true,\"x\"],\"s\":\"plain\"}]");
+}
+
+#[test]
+fn test_map() {
+	use crate::{map::Map, value::Value};
+
+	const INPUT: &str = "{\"zeta\":1,\"alpha\":2,\"mid\":3}";
+	let mut map: Map<String, u32> = crate::from_str(INPUT).unwrap();
+	assert_eq!(map.keys().map(String::as_str).collect::<STDVec<_>>(), ["zeta", "alpha", "mid"]);
+	assert_eq!(map["alpha"], 2);
+	assert_eq!(map.get_index(2), Some((&"mid".to_owned(), &3)));
+	assert_eq!(crate::to_string(&map).unwrap(), INPUT);
+
+	assert_eq!(map.insert("alpha".to_owned(), 20), Some(2));
+	assert_eq!(map.insert("omega".to_owned(), 4), None);
+	assert_eq!(map.remove("zeta"), Some(1));
+	assert_eq!(map.remove("zeta"), None);
+	assert_eq!(map.index_of("omega"), Some(2));
+	assert_eq!(crate::to_string(&map).unwrap(), "{\"alpha\":20,\"mid\":3,\"omega\":4}");
+
+	let reordered: Map<String, u32> = map.clone().into_iter().rev().collect();
+	assert_ne!(reordered, map);
+	assert_eq!(reordered.into_iter().rev().collect::<Map<_, _>>(), map);
+
+	// Keys are stored once, so they need not be cloneable.
+	#[derive(Debug, Eq, Hash, PartialEq)]
+	struct Id(u8);
+	let mut ids = Map::new();
+	assert_eq!(ids.insert(Id(2), 'b'), None);
+	*ids.get_or_insert_with(Id(1), || 'a') = 'c';
+	assert_eq!(ids.insert(Id(2), 'd'), Some('b'));
+	assert_eq!(ids.iter().collect::<STDVec<_>>(), [(&Id(2), &'d'), (&Id(1), &'c')]);
+
+	const CONFIG: &str = "{\"name\":\"x\",\"build\":{\"target\":\"wasm\",\"debug\":false},\"deps\":[]}";
+	let mut value: Value = crate::from_str(CONFIG).unwrap();
+	assert_eq!(crate::to_string(&value).unwrap(), CONFIG);
+	value["build"]["opt"] = Value::from("s");
+	assert_eq!(crate::to_string(&value).unwrap(),
+		"{\"name\":\"x\",\"build\":{\"target\":\"wasm\",\"debug\":false,\"opt\":\"s\"},\"deps\":[]}");
+}
+
+#[test]
+fn test_json_pointer() {
+	use crate::{pointer::{JsonPointer, PointerError}, value::Value};
+
+	// Examples from RFC 6901.
+	const INPUT: &str = "{\"foo\": [\"bar\", \"baz\"], \"\": 0, \"a/b\": 1, \"c%d\": 2, \"e^f\": 3, \"g|h\": 4, \"i\\\\j\": 5, \"k\\\"l\": 6, \" \": 7, \"m~n\": 8}";
+	let mut value = crate::from_str::<Value>(INPUT).unwrap().into_owned();
+	let get = |value: &Value<'static>, pointer: &str| pointer.parse::<JsonPointer>().unwrap().get(value).cloned();
+
+	assert_eq!(get(&value, ""), Some(value.clone()));
+	assert_eq!(get(&value, "/foo/0"), Some(Value::from("bar")));
+	for (pointer, expected) in [("/", 0), ("/a~1b", 1), ("/c%d", 2), ("/e^f", 3), ("/g|h", 4),
+			("/i\\j", 5), ("/k\"l", 6), ("/ ", 7), ("/m~0n", 8)] {
+		assert_eq!(get(&value, pointer).as_ref().and_then(Value::as_u64), Some(expected), "{}", pointer);
+	}
+	assert_eq!(get(&value, "/foo/01"), None);
+	assert_eq!(get(&value, "/foo/2"), None);
+	assert_eq!("foo".parse::<JsonPointer>(), Err(PointerError::ExpectedSlash));
+	assert_eq!("/a~2".parse::<JsonPointer>(), Err(PointerError::InvalidEscape));
+
+	let pointer: JsonPointer = "/m~0n/a~1b/3".parse().unwrap();
+	assert_eq!(pointer.to_string(), "/m~0n/a~1b/3");
+	assert_eq!(pointer.keys(), [KeyKind::Object(Cow::Borrowed("m~n")),
+		KeyKind::Object(Cow::Borrowed("a/b")), KeyKind::Array(3)]);
+	assert_eq!(JsonPointer::from(pointer.keys()), pointer);
+
+	let foo: JsonPointer = "/foo".parse().unwrap();
+	let insert = |value: &mut Value<'static>, pointer: &str, new: &'static str|
+		pointer.parse::<JsonPointer>().unwrap().insert(value, Value::from(new));
+	assert_eq!(insert(&mut value, "/foo/1", "qux"), Ok(None));
+	assert_eq!(insert(&mut value, "/foo/-", "end"), Ok(None));
+	assert_eq!(insert(&mut value, "/foo/9", "far"), Err(PointerError::InvalidIndex));
+	assert_eq!(insert(&mut value, "/missing/x", "no"), Err(PointerError::NotFound));
+	assert_eq!(insert(&mut value, "/ ", "space").unwrap().and_then(|value| value.as_u64()), Some(7));
+	assert_eq!(get(&value, "/ "), Some(Value::from("space")));
+	assert_eq!(crate::to_string(foo.get(&value).unwrap()).unwrap(), "[\"bar\",\"qux\",\"baz\",\"end\"]");
+
+	assert_eq!("/foo/0".parse::<JsonPointer>().unwrap().remove(&mut value), Some(Value::from("bar")));
+	assert_eq!("/a~1b".parse::<JsonPointer>().unwrap().remove(&mut value).and_then(|value| value.as_u64()), Some(1));
+	assert_eq!("/a~1b".parse::<JsonPointer>().unwrap().remove(&mut value), None);
+	*"/foo/0".parse::<JsonPointer>().unwrap().get_mut(&mut value).unwrap() = Value::Null;
+	assert_eq!(crate::to_string(foo.get(&value).unwrap()).unwrap(), "[null,\"baz\",\"end\"]");
+}
+
+#[test]
+fn test_from_str_at() {
+	use crate::{deserialize::{deserialize_at, from_str_at}, pointer::JsonPointer};
+
+	const INPUT: &str = "{\"data\": [{\"id\": 1}, {\"id\": \"bad\"}, {\"id\": 3, \"tags\": [\"a\", \"b\"]}], \"metadata\": {\"name\": \"x\", \"version\": 7}}";
+	let pointer = |pointer: &str| pointer.parse::<JsonPointer>().unwrap();
+
+	assert_eq!(from_str_at::<u32>(INPUT, &pointer("/metadata/version")).unwrap(), 7);
+	assert_eq!(from_str_at::<u32>(INPUT, &pointer("/data/2/id")).unwrap(), 3);
+	assert_eq!(from_str_at::<STDVec<String>>(INPUT, &pointer("/data/2/tags")).unwrap(), ["a", "b"]);
+	assert_eq!(from_str_at::<Cow<str>>(INPUT, &pointer("/metadata/name")).unwrap(), "x");
+
+	let errors = from_str_at::<u32>(INPUT, &pointer("/metadata/missing")).unwrap_err().into_errors();
+	assert!(matches!(errors[0].kind, ErrorKind::PathNotFound));
+	assert_eq!(errors[0].to_string(), "path not found at .metadata.missing");
+
+	let errors = from_str_at::<u32>(INPUT, &pointer("/data/5/id")).unwrap_err().into_errors();
+	assert!(matches!(errors[0].kind, ErrorKind::PathNotFound));
+	assert_eq!(errors[0].path, [KeyKind::Object(Cow::Borrowed("data")), KeyKind::Array(5)]);
+
+	let errors = from_str_at::<u32>(INPUT, &pointer("/data/1/id")).unwrap_err().into_errors();
+	assert!(matches!(errors[0].kind, ErrorKind::Semantic(_)));
+	assert_eq!(errors[0].path, [KeyKind::Object(Cow::Borrowed("data")), KeyKind::Array(1),
+		KeyKind::Object(Cow::Borrowed("id"))]);
+
+	let errors = from_str_at::<u32>("{\"a\": 1} 2", &pointer("/a")).unwrap_err().into_errors();
+	assert!(matches!(errors[0].kind, ErrorKind::Syntax(_)));
+
+	// The last of duplicated keys is used, as by `from_str`.
+	const DUPLICATED: &str = "{\"a\": {\"b\": 1}, \"c\": {}, \"a\": {\"b\": 2}}";
+	assert_eq!(from_str_at::<u32>(DUPLICATED, &pointer("/a/b")).unwrap(), 2);
+	assert_eq!(crate::from_str::<STDHashMap<String, STDHashMap<String, u32>>>(DUPLICATED).unwrap()["a"]["b"], 2);
+
+	let mut policy = Policy {duplicates: Duplicates::Report, ..Default::default()};
+	let mut deserializer = Deserializer::new(DUPLICATED);
+	let value = ValueDeserializer::new(&mut deserializer)
+		.and_then(|value| deserialize_at::<u32, _>(value, pointer("/a/b").tokens(), &mut policy))
+		.unwrap();
+	assert_eq!(value, Some(2));
+	assert_eq!(policy.errors, ["duplicate key in object"]);
 }
@@ -0,0 +1,484 @@
+use super::{
+	deserialize::{
+		error::{ErrorContext, JSONType, SyntaxError},
+		r#trait::{Deserialize, DeserializeKey},
+		Number, ValueDeserializer
+	},
+	serialize::{
+		format::Formatter as SerializeFormatter, r#trait::{Serialize, SerializeKey},
+		Error as SerializeError, ValueSerializer
+	}
+};
+use std::{
+	borrow::Cow,
+	error::Error,
+	fmt::{Display, Formatter, Result as FMTResult},
+	str::FromStr,
+	time::{Duration, SystemTime, UNIX_EPOCH}
+};
+
+const NANOS_PER_SECOND: i128 = 1_000_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
+/// 0000-01-01T00:00:00Z
+const MIN_SECONDS: i64 = -62_167_219_200;
+/// 9999-12-31T23:59:59Z
+const MAX_SECONDS: i64 = 253_402_300_799;
+
+/// An instant in time between the years 0000 and 9999 inclusive, stored in
+/// UTC with nanosecond precision.
+///
+/// Deserializes from RFC 3339 strings such as `1985-04-12T23:20:50.52Z` or
+/// `1996-12-19T16:39:57-08:00`. Use [`EpochSeconds`] or [`EpochMillis`] to
+/// also accept numbers relative to the Unix epoch.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Timestamp {
+	seconds: i64,
+	nanos: u32
+}
+
+impl Timestamp {
+	pub const UNIX_EPOCH: Self = Self {seconds: 0, nanos: 0};
+
+	/// Creates a timestamp from seconds and nanoseconds since the Unix epoch,
+	/// or `None` if it lies outside the supported range or `nanos` is a
+	/// whole second or more.
+	pub fn from_unix(seconds: i64, nanos: u32) -> Option<Self> {
+		((MIN_SECONDS..=MAX_SECONDS).contains(&seconds)
+			&& i128::from(nanos) < NANOS_PER_SECOND)
+				.then_some(Self {seconds, nanos})
+	}
+
+	/// Parses an RFC 3339 timestamp. A leap second (`:60`) is only accepted
+	/// at 23:59 UTC, and treated as the first second of the following day.
+	pub fn parse(string: &str) -> Result<Self, TimestampError> {
+		let mut parser = Parser {string, index: 0};
+
+		let year = parser.digits(4, "a four digit year")?;
+		parser.expect(&['-'], "'-'")?;
+		let month = parser.digits(2, "a two digit month")?;
+		parser.expect(&['-'], "'-'")?;
+		let day = parser.digits(2, "a two digit day")?;
+		parser.expect(&['T', 't', ' '], "'T'")?;
+		let hour = parser.digits(2, "a two digit hour")?;
+		parser.expect(&[':'], "':'")?;
+		let minute = parser.digits(2, "a two digit minute")?;
+		parser.expect(&[':'], "':'")?;
+		let second = parser.digits(2, "a two digit second")?;
+
+		let nanos = match parser.peek() {
+			Some('.') => {
+				parser.index += 1;
+				let start = parser.index;
+				while let Some('0'..='9') = parser.peek() {
+					parser.index += 1;
+				}
+				let fraction = &string[start..parser.index];
+				if fraction.is_empty() {
+					return Err(parser.error("a fractional digit"))
+				}
+				fraction.bytes().chain(std::iter::repeat(b'0')).take(9)
+					.fold(0, |nanos, digit| nanos * 10 + u32::from(digit - b'0'))
+			},
+			_ => 0
+		};
+
+		let offset = match parser.peek() {
+			Some('Z' | 'z') => {parser.index += 1; 0},
+			Some(sign @ ('+' | '-')) => {
+				parser.index += 1;
+				let hours = parser.digits(2, "a two digit offset hour")?;
+				parser.expect(&[':'], "':'")?;
+				let minutes = parser.digits(2, "a two digit offset minute")?;
+				if hours > 23 || minutes > 59 {
+					return Err(TimestampError::OffsetOutOfRange)
+				}
+				let offset = i64::from(hours * 60 + minutes) * 60;
+				if sign == '-' {-offset} else {offset}
+			},
+			_ => return Err(parser.error("'Z' or an offset"))
+		};
+		if parser.index != string.len() {
+			return Err(parser.error("the end of the timestamp"))
+		}
+
+		if !(1..=12).contains(&month) {
+			return Err(TimestampError::MonthOutOfRange(month as u8))
+		}
+		if day == 0 || day > days_in_month(year, month) {
+			return Err(TimestampError::DayOutOfRange {
+				year: year as u16,
+				month: month as u8,
+				day: day as u8
+			})
+		}
+		if hour > 23 {
+			return Err(TimestampError::HourOutOfRange(hour as u8))
+		}
+		if minute > 59 {
+			return Err(TimestampError::MinuteOutOfRange(minute as u8))
+		}
+		// Leap seconds are only ever inserted at the end of a UTC day.
+		let leap_second_allowed = (i64::from(hour * 3_600 + minute * 60) - offset)
+			.rem_euclid(SECONDS_PER_DAY) == SECONDS_PER_DAY - 60;
+		if second > 60 || second == 60 && !leap_second_allowed {
+			return Err(TimestampError::SecondOutOfRange(second as u8))
+		}
+
+		let seconds = days_from_civil(year, month, day) * SECONDS_PER_DAY
+			+ i64::from(hour * 3_600 + minute * 60 + second)
+			- offset;
+		Self::from_unix(seconds, nanos).ok_or(TimestampError::OutOfRange)
+	}
+
+	/// Whole seconds since the Unix epoch, negative for earlier instants.
+	#[inline]
+	pub fn unix_seconds(self) -> i64 {
+		self.seconds
+	}
+
+	/// Nanoseconds past [`unix_seconds`](Self::unix_seconds), always less
+	/// than a whole second.
+	#[inline]
+	pub fn subsec_nanos(self) -> u32 {
+		self.nanos
+	}
+
+	/// Milliseconds since the Unix epoch, rounded towards negative infinity.
+	#[inline]
+	pub fn unix_millis(self) -> i64 {
+		self.seconds * 1_000 + i64::from(self.nanos / 1_000_000)
+	}
+
+	#[inline]
+	fn unix_nanos(self) -> i128 {
+		i128::from(self.seconds) * NANOS_PER_SECOND + i128::from(self.nanos)
+	}
+
+	fn from_unix_nanos(nanos: i128) -> Option<Self> {
+		let seconds = i64::try_from(nanos.div_euclid(NANOS_PER_SECOND)).ok()?;
+		Self::from_unix(seconds, nanos.rem_euclid(NANOS_PER_SECOND) as u32)
+	}
+}
+
+impl Display for Timestamp {
+	/// Formats as RFC 3339 in UTC, with as many fractional digits as needed.
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		let days = self.seconds.div_euclid(SECONDS_PER_DAY);
+		let time = self.seconds.rem_euclid(SECONDS_PER_DAY);
+		let (year, month, day) = civil_from_days(days);
+		write!(f, "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day,
+			time / 3_600, time / 60 % 60, time % 60)?;
+
+		if self.nanos != 0 {
+			let fraction = format!("{:09}", self.nanos);
+			write!(f, ".{}", fraction.trim_end_matches('0'))?;
+		}
+		write!(f, "Z")
+	}
+}
+
+impl FromStr for Timestamp {
+	type Err = TimestampError;
+
+	fn from_str(string: &str) -> Result<Self, Self::Err> {
+		Self::parse(string)
+	}
+}
+
+impl TryFrom<SystemTime> for Timestamp {
+	type Error = TimestampError;
+
+	fn try_from(time: SystemTime) -> Result<Self, Self::Error> {
+		let nanos = match time.duration_since(UNIX_EPOCH) {
+			Ok(after) => i128::try_from(after.as_nanos()),
+			Err(before) => i128::try_from(before.duration().as_nanos())
+				.map(|nanos| -nanos)
+		};
+		nanos.ok()
+			.and_then(Self::from_unix_nanos)
+			.ok_or(TimestampError::OutOfRange)
+	}
+}
+
+impl TryFrom<Timestamp> for SystemTime {
+	type Error = TimestampError;
+
+	fn try_from(timestamp: Timestamp) -> Result<Self, Self::Error> {
+		let time = if timestamp.seconds >= 0 {
+			UNIX_EPOCH.checked_add(Duration::new(timestamp.seconds as u64,
+				timestamp.nanos))
+		} else {
+			UNIX_EPOCH.checked_sub(Duration::from_secs(timestamp.seconds.unsigned_abs()))
+				.and_then(|time| time.checked_add(Duration::from_nanos(timestamp.nanos.into())))
+		};
+		time.ok_or(TimestampError::OutOfRange)
+	}
+}
+
+impl<'s, E> Deserialize<'s, E> for Timestamp
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		match value {
+			ValueDeserializer::String(string) =>
+				Ok(parse_reported(&string.accept()?, error_context)),
+			unexpected => {
+				error_context.report_unexpected_type(unexpected.kind(), &[JSONType::String]);
+				Ok(None)
+			}
+		}
+	}
+}
+
+impl<'s, E> DeserializeKey<'s, E> for Timestamp
+		where E: ErrorContext<'s> {
+	#[inline]
+	fn deserialize_key(key: Cow<'s, str>, error_context: &mut E) -> Option<Self> {
+		parse_reported(&key, error_context)
+	}
+}
+
+impl Serialize for Timestamp {
+	#[inline]
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), SerializeError>
+			where F: SerializeFormatter {
+		value.string(&self.to_string())
+	}
+}
+
+impl SerializeKey for Timestamp {
+	#[inline]
+	fn serialize_key(&self) -> Cow<'_, str> {
+		Cow::Owned(self.to_string())
+	}
+}
+
+macro_rules! impl_epochs {
+	($($(#[$attribute:meta])* $epoch:ident: $nanos_per_unit:literal),*) => {
+		$(
+			$(#[$attribute])*
+			#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+			pub struct $epoch(pub Timestamp);
+
+			impl<'s, E> Deserialize<'s, E> for $epoch
+					where E: ErrorContext<'s> {
+				#[inline]
+				fn deserialize<'d>(value: ValueDeserializer<'d, 's>,
+						error_context: &mut E) -> Result<Option<Self>, SyntaxError> {
+					match value {
+						ValueDeserializer::Number(number) => {
+							let timestamp = number_to_nanos(&number.accept()?, $nanos_per_unit)
+								.and_then(Timestamp::from_unix_nanos);
+							if timestamp.is_none() {
+								error_context.report_timestamp_invalid(TimestampError::OutOfRange);
+							}
+							Ok(timestamp.map($epoch))
+						},
+						ValueDeserializer::String(string) =>
+							Ok(parse_reported(&string.accept()?, error_context).map($epoch)),
+						unexpected => {
+							error_context.report_unexpected_type(unexpected.kind(),
+								&[JSONType::Number, JSONType::String]);
+							Ok(None)
+						}
+					}
+				}
+			}
+
+			impl Serialize for $epoch {
+				#[inline]
+				fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), SerializeError>
+						where F: SerializeFormatter {
+					value.raw(&nanos_to_number(self.0.unix_nanos(), $nanos_per_unit))
+				}
+			}
+
+			impl From<$epoch> for Timestamp {
+				#[inline]
+				fn from(epoch: $epoch) -> Self {
+					epoch.0
+				}
+			}
+		)*
+	}
+}
+
+impl_epochs! {
+	/// A [`Timestamp`] that also deserializes from a number of seconds since
+	/// the Unix epoch, which may have a fractional component.
+	EpochSeconds: 1_000_000_000,
+	/// A [`Timestamp`] that also deserializes from a number of milliseconds
+	/// since the Unix epoch, which may have a fractional component.
+	EpochMillis: 1_000_000
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum TimestampError {
+	/// The string didn't follow the RFC 3339 grammar at the given byte index.
+	Unexpected {
+		index: usize,
+		expected: &'static str
+	},
+	MonthOutOfRange(u8),
+	DayOutOfRange {
+		year: u16,
+		month: u8,
+		day: u8
+	},
+	HourOutOfRange(u8),
+	MinuteOutOfRange(u8),
+	SecondOutOfRange(u8),
+	OffsetOutOfRange,
+	/// The instant lies outside the years 0000 to 9999, or outside what the
+	/// target of a conversion can represent.
+	OutOfRange
+}
+
+impl Display for TimestampError {
+	fn fmt(&self, f: &mut Formatter) -> FMTResult {
+		match self {
+			Self::Unexpected {index, expected} =>
+				write!(f, "expected {} at index {}", expected, index),
+			Self::MonthOutOfRange(month) =>
+				write!(f, "month {} is out of range", month),
+			Self::DayOutOfRange {year, month, day} =>
+				write!(f, "day {} is out of range for {:04}-{:02}", day, year, month),
+			Self::HourOutOfRange(hour) =>
+				write!(f, "hour {} is out of range", hour),
+			Self::MinuteOutOfRange(minute) =>
+				write!(f, "minute {} is out of range", minute),
+			Self::SecondOutOfRange(second) =>
+				write!(f, "second {} is out of range", second),
+			Self::OffsetOutOfRange =>
+				write!(f, "offset is out of range"),
+			Self::OutOfRange =>
+				write!(f, "timestamp is out of range")
+		}
+	}
+}
+
+impl Error for TimestampError {}
+
+struct Parser<'s> {
+	string: &'s str,
+	index: usize
+}
+
+impl<'s> Parser<'s> {
+	fn peek(&self) -> Option<char> {
+		self.string[self.index..].chars().next()
+	}
+
+	fn error(&self, expected: &'static str) -> TimestampError {
+		TimestampError::Unexpected {index: self.index, expected}
+	}
+
+	fn expect(&mut self, chars: &[char], expected: &'static str)
+			-> Result<(), TimestampError> {
+		match self.peek() {
+			Some(char) if chars.contains(&char) => {
+				self.index += char.len_utf8();
+				Ok(())
+			},
+			_ => Err(self.error(expected))
+		}
+	}
+
+	fn digits(&mut self, count: usize, expected: &'static str)
+			-> Result<u32, TimestampError> {
+		let digits = self.string.get(self.index..self.index + count)
+			.filter(|digits| digits.bytes().all(|digit| digit.is_ascii_digit()))
+			.ok_or_else(|| self.error(expected))?;
+		self.index += count;
+		Ok(digits.parse().expect("digits were parsed incorrectly"))
+	}
+}
+
+fn parse_reported<'s, E>(string: &str, error_context: &mut E) -> Option<Timestamp>
+		where E: ErrorContext<'s> {
+	Timestamp::parse(string)
+		.map_err(|error| error_context.report_timestamp_invalid(error))
+		.ok()
+}
+
+/// Converts a number of some unit to nanoseconds exactly, or `None` if it
+/// overflows.
+fn number_to_nanos(number: &Number, nanos_per_unit: i128) -> Option<i128> {
+	let fraction = number.fraction().unwrap_or_default();
+	let digits = number.base().bytes().chain(fraction.bytes())
+		.try_fold(0i128, |value, digit| value.checked_mul(10)?
+			.checked_add(i128::from(digit - b'0')))?;
+	let exponent: i32 = match number.exponent() {
+		Some(exponent) => exponent.parse().ok()?,
+		None => 0
+	};
+	let exponent = if number.exponent_positive {exponent} else {-exponent}
+		- i32::try_from(fraction.len()).ok()?;
+
+	let nanos = digits.checked_mul(nanos_per_unit)?;
+	let nanos = if exponent >= 0 {
+		nanos.checked_mul(10i128.checked_pow(exponent as u32)?)?
+	} else {
+		10i128.checked_pow(exponent.unsigned_abs())
+			.map_or(0, |divisor| nanos / divisor)
+	};
+	Some(if number.base_positive {nanos} else {-nanos})
+}
+
+/// Formats nanoseconds as an exact number of some unit.
+fn nanos_to_number(nanos: i128, nanos_per_unit: i128) -> String {
+	let sign = if nanos < 0 {"-"} else {""};
+	let nanos = nanos.unsigned_abs();
+	let nanos_per_unit = nanos_per_unit.unsigned_abs();
+	let (whole, fraction) = (nanos / nanos_per_unit, nanos % nanos_per_unit);
+	match fraction {
+		0 => format!("{}{}", sign, whole),
+		_ => {
+			let width = nanos_per_unit.ilog10() as usize;
+			let fraction = format!("{:0width$}", fraction, width = width);
+			format!("{}{}.{}", sign, whole, fraction.trim_end_matches('0'))
+		}
+	}
+}
+
+fn is_leap_year(year: u32) -> bool {
+	year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u32, month: u32) -> u32 {
+	match month {
+		2 if is_leap_year(year) => 29,
+		2 => 28,
+		4 | 6 | 9 | 11 => 30,
+		_ => 31
+	}
+}
+
+/// Days since the Unix epoch of a proleptic Gregorian date.
+fn days_from_civil(year: u32, month: u32, day: u32) -> i64 {
+	let year = i64::from(year) - i64::from(month <= 2);
+	let era = year.div_euclid(400);
+	let year_of_era = year.rem_euclid(400);
+	let month = i64::from(month);
+	let day_of_year = (153 * (month + if month > 2 {-3} else {9}) + 2) / 5
+		+ i64::from(day) - 1;
+	let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100
+		+ day_of_year;
+	era * 146_097 + day_of_era - 719_468
+}
+
+/// The proleptic Gregorian date of a number of days since the Unix epoch.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+	let days = days + 719_468;
+	let era = days.div_euclid(146_097);
+	let day_of_era = days.rem_euclid(146_097);
+	let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524
+		- day_of_era / 146_096) / 365;
+	let day_of_year = day_of_era
+		- (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+	let month = (5 * day_of_year + 2) / 153;
+	let day = day_of_year - (153 * month + 2) / 5 + 1;
+	let month = if month < 10 {month + 3} else {month - 9};
+	(year_of_era + era * 400 + i64::from(month <= 2), month, day)
+}
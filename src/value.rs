@@ -0,0 +1,283 @@
+//! An untyped representation of any JSON value.
+
+use super::{
+	deserialize::{
+		error::{ErrorContext, JSONType, SyntaxError},
+		r#trait::Deserialize,
+		Number, ValueDeserializer
+	},
+	map::Map,
+	serialize::{format::Formatter, r#trait::Serialize, Error as SerializeError, ValueSerializer}
+};
+use std::{borrow::Cow, ops::{Index, IndexMut}};
+
+/// The entries of an object, in the order they appear in the document.
+pub type Object<'s> = Map<Cow<'s, str>, Value<'s>>;
+
+/// Any JSON value, borrowing strings and numbers from the input where it can.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Value<'s> {
+	Object(Object<'s>),
+	Array(Vec<Value<'s>>),
+	String(Cow<'s, str>),
+	Number(Number<'s>),
+	Boolean(bool),
+	#[default]
+	Null
+}
+
+/// What indexing a value by a missing key or position returns.
+static NULL: Value<'static> = Value::Null;
+
+impl<'s> Value<'s> {
+	#[inline]
+	pub fn kind(&self) -> JSONType {
+		match self {
+			Self::Object(_) => JSONType::Object,
+			Self::Array(_) => JSONType::Array,
+			Self::String(_) => JSONType::String,
+			Self::Number(_) => JSONType::Number,
+			Self::Boolean(_) => JSONType::Boolean,
+			Self::Null => JSONType::Null
+		}
+	}
+
+	#[inline]
+	pub fn is_null(&self) -> bool {
+		matches!(self, Self::Null)
+	}
+
+	#[inline]
+	pub fn as_object(&self) -> Option<&Object<'s>> {
+		match self {
+			Self::Object(object) => Some(object),
+			_ => None
+		}
+	}
+
+	#[inline]
+	pub fn as_object_mut(&mut self) -> Option<&mut Object<'s>> {
+		match self {
+			Self::Object(object) => Some(object),
+			_ => None
+		}
+	}
+
+	#[inline]
+	pub fn as_array(&self) -> Option<&[Value<'s>]> {
+		match self {
+			Self::Array(array) => Some(array),
+			_ => None
+		}
+	}
+
+	#[inline]
+	pub fn as_array_mut(&mut self) -> Option<&mut Vec<Value<'s>>> {
+		match self {
+			Self::Array(array) => Some(array),
+			_ => None
+		}
+	}
+
+	#[inline]
+	pub fn as_str(&self) -> Option<&str> {
+		match self {
+			Self::String(string) => Some(string),
+			_ => None
+		}
+	}
+
+	#[inline]
+	pub fn as_number(&self) -> Option<&Number<'s>> {
+		match self {
+			Self::Number(number) => Some(number),
+			_ => None
+		}
+	}
+
+	/// The number as the nearest float.
+	#[inline]
+	pub fn as_f64(&self) -> Option<f64> {
+		self.as_number().and_then(|number| number.source.parse().ok())
+	}
+
+	/// The number if it is written as an integer that fits.
+	#[inline]
+	pub fn as_i64(&self) -> Option<i64> {
+		self.as_number().and_then(|number| number.source.parse().ok())
+	}
+
+	/// The number if it is written as an integer that fits.
+	#[inline]
+	pub fn as_u64(&self) -> Option<u64> {
+		self.as_number().and_then(|number| number.source.parse().ok())
+	}
+
+	#[inline]
+	pub fn as_bool(&self) -> Option<bool> {
+		match self {
+			Self::Boolean(boolean) => Some(*boolean),
+			_ => None
+		}
+	}
+
+	/// The value of an object's entry.
+	#[inline]
+	pub fn get(&self, key: &str) -> Option<&Value<'s>> {
+		self.as_object().and_then(|object| object.get(key))
+	}
+
+	#[inline]
+	pub fn get_mut(&mut self, key: &str) -> Option<&mut Value<'s>> {
+		self.as_object_mut().and_then(|object| object.get_mut(key))
+	}
+
+	/// The element of an array.
+	#[inline]
+	pub fn get_index(&self, index: usize) -> Option<&Value<'s>> {
+		self.as_array().and_then(|array| array.get(index))
+	}
+
+	#[inline]
+	pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Value<'s>> {
+		self.as_array_mut().and_then(|array| array.get_mut(index))
+	}
+
+	/// Replaces the value with `null`, returning it.
+	#[inline]
+	pub fn take(&mut self) -> Self {
+		std::mem::take(self)
+	}
+
+	/// Copies whatever is borrowed from the input.
+	pub fn into_owned(self) -> Value<'static> {
+		match self {
+			Self::Object(object) => Value::Object(object.into_iter()
+				.map(|(key, value)| (Cow::Owned(key.into_owned()), value.into_owned()))
+				.collect()),
+			Self::Array(array) => Value::Array(array.into_iter().map(Self::into_owned).collect()),
+			Self::String(string) => Value::String(Cow::Owned(string.into_owned())),
+			Self::Number(number) => Value::Number(number.into_owned()),
+			Self::Boolean(boolean) => Value::Boolean(boolean),
+			Self::Null => Value::Null
+		}
+	}
+}
+
+/// Indexing by a key missing from an object, or any key of another value,
+/// gives `null`.
+impl<'s> Index<&str> for Value<'s> {
+	type Output = Value<'s>;
+
+	#[inline]
+	fn index(&self, key: &str) -> &Self::Output {
+		self.get(key).unwrap_or(&NULL)
+	}
+}
+
+/// Indexing by a key missing from an object inserts it with `null`, and
+/// indexing `null` turns it into an empty object first.
+///
+/// # Panics
+///
+/// Panics if the value is neither an object nor `null`.
+impl<'s> IndexMut<&str> for Value<'s> {
+	fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+		if self.is_null() {
+			*self = Self::Object(Object::new());
+		}
+		match self {
+			Self::Object(object) => object.get_or_insert_with(Cow::Owned(key.to_owned()), Self::default),
+			other => panic!("cannot index {} by a key", other.kind().mention_by_noun())
+		}
+	}
+}
+
+/// Indexing by a position out of an array's bounds, or any position of
+/// another value, gives `null`.
+impl<'s> Index<usize> for Value<'s> {
+	type Output = Value<'s>;
+
+	#[inline]
+	fn index(&self, index: usize) -> &Self::Output {
+		self.get_index(index).unwrap_or(&NULL)
+	}
+}
+
+/// # Panics
+///
+/// Panics if the value is not an array or the position is out of its bounds.
+impl<'s> IndexMut<usize> for Value<'s> {
+	fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+		match self {
+			Self::Array(array) => &mut array[index],
+			other => panic!("cannot index {} by a position", other.kind().mention_by_noun())
+		}
+	}
+}
+
+impl<'s> From<bool> for Value<'s> {
+	#[inline]
+	fn from(boolean: bool) -> Self {
+		Self::Boolean(boolean)
+	}
+}
+
+impl<'s> From<&'s str> for Value<'s> {
+	#[inline]
+	fn from(string: &'s str) -> Self {
+		Self::String(Cow::Borrowed(string))
+	}
+}
+
+impl<'s> From<String> for Value<'s> {
+	#[inline]
+	fn from(string: String) -> Self {
+		Self::String(Cow::Owned(string))
+	}
+}
+
+impl<'s> From<Vec<Value<'s>>> for Value<'s> {
+	#[inline]
+	fn from(array: Vec<Value<'s>>) -> Self {
+		Self::Array(array)
+	}
+}
+
+impl<'s> From<Object<'s>> for Value<'s> {
+	#[inline]
+	fn from(object: Object<'s>) -> Self {
+		Self::Object(object)
+	}
+}
+
+impl<'s, E> Deserialize<'s, E> for Value<'s>
+		where E: ErrorContext<'s> {
+	fn deserialize<'d>(value: ValueDeserializer<'d, 's>, error_context: &mut E)
+			-> Result<Option<Self>, SyntaxError> {
+		Ok(match value {
+			ValueDeserializer::Object(_) =>
+				Object::deserialize(value, error_context)?.map(Self::Object),
+			ValueDeserializer::Array(_) =>
+				Vec::deserialize(value, error_context)?.map(Self::Array),
+			ValueDeserializer::String(string) => Some(Self::String(string.accept()?)),
+			ValueDeserializer::Number(number) => Some(Self::Number(number.accept()?)),
+			ValueDeserializer::Boolean(boolean) => Some(Self::Boolean(boolean)),
+			ValueDeserializer::Null => Some(Self::Null)
+		})
+	}
+}
+
+impl Serialize for Value<'_> {
+	fn serialize<F>(&self, value: ValueSerializer<'_, '_, F>) -> Result<(), SerializeError>
+			where F: Formatter {
+		match self {
+			Self::Object(object) => object.serialize(value),
+			Self::Array(array) => array.serialize(value),
+			Self::String(string) => value.string(string),
+			Self::Number(number) => value.raw(&number.source),
+			Self::Boolean(boolean) => value.boolean(*boolean),
+			Self::Null => value.null()
+		}
+	}
+}